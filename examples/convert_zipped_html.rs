@@ -1,7 +1,4 @@
-use std::{
-    fs::File,
-    io::{BufReader, Write},
-};
+use std::{fs::File, io::BufReader, path::Path};
 
 use aqua_troll_log_reader::{AquaTrollLogError, AquaTrollLogReader};
 
@@ -15,20 +12,8 @@ fn main() -> Result<(), AquaTrollLogError> {
     let reader = AquaTrollLogReader::default();
     let log = reader.read_zipped_html(&mut file)?;
 
-    // Write attr to json file
-    let mut json_file = File::create("ex_html_attr.json")?;
-    let json_str = serde_json::to_string_pretty(&log.attr).unwrap();
-    json_file.write_all(json_str.as_bytes())?;
-
-    // Write log_note to csv file
-    if let Some(ref log_note) = log.log_note {
-        let log_note_csv_file = File::create("ex_html_note.csv")?;
-        log_note.write_csv(log_note_csv_file)?;
-    }
-
-    // Write log_data to csv file
-    let log_data_csv_file = File::create("ex_html_data.csv")?;
-    log.log_data.write_csv(log_data_csv_file)?;
+    // Write attr to json file, log_note and log_data to csv files
+    log.export_bundle(Path::new("."), "ex_html")?;
 
     Ok(())
 }
@@ -1,27 +1,10 @@
 use std::env;
 use std::fs::File;
 use std::path::Path;
-use std::rc::Rc;
 
-use chrono::NaiveDateTime;
+use chrono::Local;
 
-use aqua_troll_log_reader::{AquaTrollLogError, AquaTrollLogReader, DateTimeParserFnRef};
-
-fn datetime_str_parser(datetime: &str) -> Result<NaiveDateTime, AquaTrollLogError> {
-    let datetime = if datetime.contains("上午") {
-        datetime.replace("上午", "AM")
-    } else if datetime.contains("下午") {
-        datetime.replace("下午", "PM")
-    } else {
-        datetime.to_string()
-    };
-
-    Ok(
-        NaiveDateTime::parse_from_str(&datetime, "%Y/%-m/%-d %p %I:%M:%S")
-            .or_else(|_| NaiveDateTime::parse_from_str(&datetime, "%Y/%-m/%-d %I:%M:%S %p"))
-            .or_else(|_| NaiveDateTime::parse_from_str(&datetime, "%Y-%-m-%-d %H:%M:%S"))?,
-    )
-}
+use aqua_troll_log_reader::{AquaTrollLogError, AquaTrollLogReader};
 
 fn main() -> Result<(), AquaTrollLogError> {
     let args: Vec<String> = env::args().collect();
@@ -40,13 +23,12 @@ fn main() -> Result<(), AquaTrollLogError> {
     };
 
     let mut file = File::open(input)?;
-    let datetime_parser = Rc::new(datetime_str_parser) as DateTimeParserFnRef;
-    let log_reader = AquaTrollLogReader::new(datetime_parser.into());
+    let log_reader = AquaTrollLogReader::default();
     let log = log_reader.read_txt(&mut file)?;
 
-    // Write log_data to csv file
+    // Write log_data to csv file with local timestamps
     let log_data_csv_file = File::create(format!("{output}.csv"))?;
-    log.log_data.write_csv(log_data_csv_file)?;
+    log.to_csv(log_data_csv_file, &Local, None)?;
 
     Ok(())
 }
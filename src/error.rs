@@ -1,9 +1,33 @@
-#[derive(thiserror::Error, Debug)]
+/// Errors of CSV rows dropped from a log, with the log of the rows read
+#[derive(Debug)]
 pub struct ErrorWithPartialResult {
     pub result: Box<crate::AquaTrollLogData>,
     pub errors: Vec<csv::Error>,
 }
 
+impl ErrorWithPartialResult {
+    /// Line numbers of the dropped rows, in the order of `errors`, errors without a position
+    /// are skipped
+    pub fn line_numbers(&self) -> Vec<usize> {
+        self.errors
+            .iter()
+            .filter_map(|e| e.position().map(|p| p.line() as usize))
+            .collect()
+    }
+
+    /// Number of rows dropped from the log, one of each error
+    pub fn dropped_rows(&self) -> usize {
+        self.errors.len()
+    }
+}
+
+impl std::error::Error for ErrorWithPartialResult {
+    /// The first of the CSV errors
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.errors.first().map(|e| e as _)
+    }
+}
+
 impl std::fmt::Display for ErrorWithPartialResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Data log error with partial result:")?;
@@ -34,12 +58,57 @@ pub enum AquaTrollLogError {
     SerdeJsonError(#[from] serde_json::Error),
     #[error(transparent)]
     ZipError(#[from] zip::result::ZipError),
+    #[cfg(feature = "arrow")]
+    #[error(transparent)]
+    ArrowError(#[from] arrow_schema::ArrowError),
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    ParquetError(#[from] parquet::errors::ParquetError),
+    #[cfg(feature = "polars")]
+    #[error(transparent)]
+    PolarsError(#[from] polars::error::PolarsError),
     #[error("Unexpected EOF")]
     UnexpectedEof,
     #[error("html file: section header not found")]
     SectionHeaderNotFound,
     #[error("Invalid Data")]
     InvalidData,
+    #[error("Line {line_number} of table: expected {expected} columns, found {found}")]
+    ColumnCountMismatch {
+        line_number: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Invalid boolean value: {0}")]
+    InvalidBoolean(String),
+    #[error("Invalid integer value of column {column}: {value}")]
+    InvalidInteger { column: String, value: String },
+    #[error("Record Count {expected} of the log data, but {found} rows parsed")]
+    RecordCountMismatch { expected: usize, found: usize },
+    #[error("No rows in the log data")]
+    EmptyTable,
+    #[error("Unknown log format")]
+    UnknownFormat,
+    #[error("Unknown parameter: {0}")]
+    UnknownParameter(String),
+    #[error("Column not found in log data: {0}")]
+    UnknownColumn(String),
+    #[error("Parameter not found in log data: {0}")]
+    ParameterNotFound(String),
+    #[error("Unknown sensor type: {0}")]
+    UnknownSensorType(String),
+    #[error("Unknown unit: {0}")]
+    UnknownUnit(String),
+    #[error("Inconsistent units of {column}: {from} and {to}")]
+    InconsistentUnits {
+        column: String,
+        from: String,
+        to: String,
+    },
+    #[error("Column not found in all tables: {0}")]
+    SchemaMismatch(String),
+    #[error("Incompatible units: {from} to {to}")]
+    IncompatibleUnits { from: String, to: String },
     #[error(transparent)]
     WithCsvPartialResult(#[from] crate::util::csv_reader::ErrorWithCsvPartialResult),
     #[error(transparent)]
@@ -1,19 +1,51 @@
 mod error;
 mod util;
 
-use std::io::{Cursor, Read, Seek};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Duration;
 
-use encoding_rs::{Encoding, ISO_8859_3, UTF_16LE};
-use encoding_rs_io::DecodeReaderBytesBuilder;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+pub use encoding_rs::Encoding;
+use encoding_rs::{ISO_8859_3, UTF_16LE, UTF_8, WINDOWS_1252};
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
 pub use error::{AquaTrollLogError, ErrorWithPartialResult};
 use serde::Serialize;
 use serde_json::{Map, Value};
+pub use util::column::{ColumnDescriptor, Extreme, PressureReference};
 pub use util::common::DateTimeParser;
 pub use util::common::DateTimeParserFnRef;
-pub use util::common::{CellValue, Table};
+use util::common::{parse_sample_rate, parse_time_zone, ReadOptions};
+pub use util::common::{
+    table_from_json, table_to_json, CellValue, ColumnType, FillStrategy, JsonOptions,
+    NormalizeOptions, Row, Table, TableBuilder, TimestampFormat, DATETIME_COLUMN,
+    DEVICE_SERIAL_KEY, PARAMETER_KEY, SENSOR_MODEL_KEY, SENSOR_SERIAL_KEY, SENSOR_TYPE_KEY,
+    UNIT_KEY,
+};
+pub use util::concat::UnitPolicy;
+pub use util::conductivity::ConductivityModel;
+pub use util::logging_mode::LoggingMode;
+pub use util::param::Parameter;
+pub use util::quality::{DataQuality, QualityTable};
+#[cfg(all(feature = "flate2", feature = "tar"))]
+use util::read_tar_gz_entries;
+pub use util::sensor::{SensorInfo, SensorType};
+#[cfg(feature = "polars")]
+use util::table_to_data_frame;
+pub use util::unit::{Dimension, Unit};
+pub use util::validate::ValidationIssue;
+use util::validate::{table_issues, unparseable_row};
+pub use util::warning::ReadWarning;
+#[cfg(feature = "arrow")]
+use util::{flatten_attr, table_to_record_batch, unflatten_attr};
 use util::{
-    read_attr, read_csv_table, read_html, read_log_data_attr, read_table, read_zipped_html,
+    peek_content_line, read_attr, read_csv_sensors, read_csv_table, read_html, read_html_sensors,
+    read_log_data_attr, read_table, read_table_layout, read_txt_sensors, read_zipped_html,
+    read_zipped_html_all, read_zipped_html_sensors, validate_txt,
 };
+pub use util::{CsvReadOptions, TxtColumnSpan, TxtRows};
 
 fn decode_reader<R: Read>(
     reader: &mut R,
@@ -24,17 +56,168 @@ fn decode_reader<R: Read>(
         .build(reader);
     let mut buf = Vec::new();
     decode.read_to_end(&mut buf)?;
+
+    // The decoder strips a single BOM, residual ones are left in the decoded text
+    let bom = "\u{feff}".as_bytes();
+    let bom_len = buf.chunks(bom.len()).take_while(|&c| c == bom).count() * bom.len();
+    buf.drain(..bom_len);
+
     Ok(Cursor::new(buf))
 }
 
+/// Strip leading BOMs of already decoded text
+fn strip_bom(content: &str) -> &str {
+    content.trim_start_matches('\u{feff}')
+}
+
+/// Encoding of a TXT log file by its BOM, or by the byte pattern of its head without BOM
+///
+/// Text of NUL bytes at most odd positions is taken as UTF-16LE (the encoding of WinSitu
+/// exports), other text as UTF-8 if valid, Windows-1252 otherwise (of legacy exports).
+fn txt_encoding(head: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(head) {
+        return encoding;
+    }
+    let odd_bytes = head.len() / 2;
+    let odd_nuls = head.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+    if odd_bytes == 0 || odd_nuls * 2 > odd_bytes {
+        return UTF_16LE;
+    }
+    match std::str::from_utf8(head) {
+        // A character may be cut off at the end of the head
+        Err(e) if e.error_len().is_some() => WINDOWS_1252,
+        _ => UTF_8,
+    }
+}
+
+/// Encoding of a CSV log file by its BOM, UTF-8 if the content is valid UTF-8, ISO-8859-3
+/// otherwise
+///
+/// The encoding is detected from the whole file, since characters out of ASCII like `°C` may
+/// only appear in notes of late rows.
+fn csv_encoding(content: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(content) {
+        return encoding;
+    }
+    match std::str::from_utf8(content) {
+        // A character may be cut off at the end of a truncated file
+        Err(e) if e.error_len().is_some() => ISO_8859_3,
+        _ => UTF_8,
+    }
+}
+
+/// Decode a TXT log file of the given encoding, or of the detected one if not given
+///
+/// The encoding is detected from the whole file, since characters out of ASCII like `°C` may
+/// only appear in the log data.
+fn decode_txt_reader<R: Read>(
+    reader: &mut R,
+    encoding: Option<&'static Encoding>,
+) -> std::io::Result<Cursor<Vec<u8>>> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+    let encoding = encoding.unwrap_or_else(|| txt_encoding(&buf));
+
+    decode_reader(&mut Cursor::new(buf), encoding)
+}
+
+/// Length of the head of a TXT log file the encoding of a streamed file is detected from,
+/// spanning the attributes and column headers of the log data
+const TXT_HEAD_LEN: u64 = 64 * 1024;
+
+/// Reader of the peeked head of a file chained with the rest
+type PeekedReader<R> = std::io::Chain<Cursor<Vec<u8>>, R>;
+
+/// Decode a TXT log file of the given encoding lazily, line by line reading, or of the
+/// encoding detected from the BOM or the head of `TXT_HEAD_LEN` bytes if not given
+fn decode_txt_buf_reader<R: Read>(
+    mut reader: R,
+    encoding: Option<&'static Encoding>,
+) -> std::io::Result<BufReader<DecodeReaderBytes<PeekedReader<R>, Vec<u8>>>> {
+    let mut head = Vec::new();
+    reader.by_ref().take(TXT_HEAD_LEN).read_to_end(&mut head)?;
+    let encoding = encoding.unwrap_or_else(|| txt_encoding(&head));
+
+    Ok(decode_buf_reader(Cursor::new(head).chain(reader), encoding))
+}
+
+/// Decode the reader lazily, line by line reading
+fn decode_buf_reader<R: Read>(
+    reader: R,
+    encoding: &'static Encoding,
+) -> BufReader<DecodeReaderBytes<R, Vec<u8>>> {
+    BufReader::new(
+        DecodeReaderBytesBuilder::new()
+            .encoding(Some(encoding))
+            .build(reader),
+    )
+}
+
+/// Supported log file formats
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LogFormat {
+    Txt,
+    Csv,
+    Html,
+    ZippedHtml,
+}
+
+impl LogFormat {
+    /// Guess the format of a log file from its name, falling back to its content
+    fn detect(name: &str, content: &[u8]) -> Option<Self> {
+        let ext = name
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("txt") => return Some(LogFormat::Txt),
+            Some("csv") => return Some(LogFormat::Csv),
+            Some("html") | Some("htm") => return Some(LogFormat::Html),
+            Some("zip") => return Some(LogFormat::ZippedHtml),
+            _ => {}
+        }
+
+        let head = &content[..usize::min(content.len(), 1024)];
+        // TXT logs of UTF-8 or Windows-1252 have no UTF-16 BOM but the report header
+        let unmarked = head.strip_prefix(b"\xef\xbb\xbf").unwrap_or(head);
+        if head.starts_with(&[0xff, 0xfe]) || unmarked.starts_with(b"Report Date:") {
+            Some(LogFormat::Txt)
+        } else if head.starts_with(b"PK\x03\x04") {
+            Some(LogFormat::ZippedHtml)
+        } else if String::from_utf8_lossy(head)
+            .to_ascii_lowercase()
+            .contains("<html")
+        {
+            Some(LogFormat::Html)
+        } else if head
+            .split(|&b| b == b'\n')
+            .next()
+            .is_some_and(|l| l.contains(&b','))
+        {
+            Some(LogFormat::Csv)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct AquaTrollLogData {
     pub attr: Map<String, Value>,
     pub log_note: Option<Table>,
     pub log_data: Table,
+    /// Data quality codes of `log_data` cells, only available for HTML logs
+    pub log_quality: Option<QualityTable>,
+    /// Recoverable problems found while reading the log, e.g. rows read leniently
+    pub warnings: Vec<ReadWarning>,
 }
 
 impl AquaTrollLogData {
+    /// Convert the log into a JSON object of `attr`, `log_note` and `log_data`
+    ///
+    /// `attr` is the object of attributes, `log_note` and `log_data` are arrays of an object
+    /// per row keyed by column name (`log_note` is null for logs without notes), as of
+    /// `table_to_json`. Timestamps are ISO 8601 strings, with the offset if the time zone is
+    /// known, e.g. `2025-01-25T16:24:59+08:00`.
     pub fn to_json(&self) -> Result<Value, AquaTrollLogError> {
         let log_note = self
             .log_note
@@ -52,6 +235,681 @@ impl AquaTrollLogData {
             ),
         ])))
     }
+
+    /// Read a log back from the JSON object of `to_json`
+    ///
+    /// Data quality codes, warnings and column metadata aren't part of the JSON and are left
+    /// empty. Columns of the same name (e.g. of the temperatures of two sensors) share a key
+    /// of the JSON objects, so they're read back as one column of the last values.
+    pub fn from_json(value: &Value) -> Result<Self, AquaTrollLogError> {
+        let object = value.as_object().ok_or(AquaTrollLogError::InvalidData)?;
+        let attr = match object.get("attr") {
+            Some(Value::Object(attr)) => attr.clone(),
+            None | Some(Value::Null) => Map::new(),
+            Some(_) => return Err(AquaTrollLogError::InvalidData),
+        };
+        let log_note = match object.get("log_note") {
+            None | Some(Value::Null) => None,
+            Some(log_note) => Some(table_from_json(log_note)?),
+        };
+        let log_data = table_from_json(object.get("log_data").unwrap_or(&Value::Null))?;
+
+        Ok(Self {
+            attr,
+            log_note,
+            log_data,
+            log_quality: None,
+            warnings: vec![],
+        })
+    }
+
+    /// Number of rows of `log_data`
+    pub fn row_count(&self) -> usize {
+        self.log_data.num_rows()
+    }
+
+    /// Number of columns of `log_data`, including the timestamp
+    pub fn column_count(&self) -> usize {
+        self.log_data.num_columns()
+    }
+
+    /// Whether `log_data` has no rows
+    pub fn is_empty(&self) -> bool {
+        self.row_count() == 0
+    }
+
+    /// Number of log notes, 0 for logs without notes
+    pub fn note_count(&self) -> usize {
+        self.log_note.as_ref().map_or(0, Table::num_rows)
+    }
+
+    /// Recoverable problems found while reading the log
+    pub fn warnings(&self) -> &[ReadWarning] {
+        &self.warnings
+    }
+
+    /// Rows of `log_data` as JSON values in column order, timestamps as ISO 8601 strings
+    pub fn rows(&self) -> impl Iterator<Item = Vec<Value>> + '_ {
+        self.log_data.json_rows(&JsonOptions::default())
+    }
+
+    /// Pretty-printed JSON of the log, as of `to_json`
+    pub fn to_json_pretty(&self) -> Result<String, AquaTrollLogError> {
+        Ok(serde_json::to_string_pretty(&self.to_json()?)?)
+    }
+
+    /// Write the pretty-printed JSON of the log, as of `to_json`
+    pub fn write_json_pretty<W: Write>(&self, writer: &mut W) -> Result<(), AquaTrollLogError> {
+        serde_json::to_writer_pretty(&mut *writer, &self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Write `log_data` as newline-delimited JSON, an object keyed by column name per row
+    pub fn to_ndjson<W: Write>(&self, writer: &mut W) -> Result<(), AquaTrollLogError> {
+        for row in self.rows() {
+            let object: Map<String, Value> =
+                self.log_data.columns.iter().cloned().zip(row).collect();
+            serde_json::to_writer(&mut *writer, &object)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Compute specific conductivity at `ref_temp` °C of each row of `log_data` from the actual
+    /// conductivity and temperature
+    ///
+    /// The model of the instrument is given by `ConductivityModel::from_attr`.
+    pub fn derive_specific_conductivity(
+        &self,
+        model: ConductivityModel,
+        ref_temp: f64,
+    ) -> Result<Vec<Option<f64>>, AquaTrollLogError> {
+        self.log_data.derive_specific_conductivity(model, ref_temp)
+    }
+
+    /// Describe columns of `log_data` by parameter, unit and sensor serial
+    pub fn columns(&self) -> Vec<ColumnDescriptor> {
+        self.log_data.column_descriptors()
+    }
+
+    /// Distinct parameters measured in `log_data`, of all sensors
+    pub fn parameters(&self) -> Vec<Parameter> {
+        self.log_data.parameters()
+    }
+
+    /// Distinct serials of the devices of `log_data` columns in column order, e.g. of gateway
+    /// exports combining devices (HTML logs), or the `Serial Number` of `Device Properties`
+    /// (TXT logs)
+    ///
+    /// Names of columns of logs combining devices are suffixed with the device serial.
+    pub fn devices(&self) -> Vec<u64> {
+        let mut devices = vec![];
+        for device in self.columns().into_iter().filter_map(|d| d.device_serial) {
+            if !devices.contains(&device) {
+                devices.push(device);
+            }
+        }
+        if devices.is_empty() {
+            devices.extend(
+                self.attr
+                    .get("Device Properties")
+                    .and_then(|section| section.get("Serial Number"))
+                    .and_then(|v| v.as_u64().or_else(|| v.as_str()?.trim().parse().ok())),
+            );
+        }
+        devices
+    }
+
+    /// Row of `log_data` of the smallest or largest reading of `parameter` as a JSON object
+    /// keyed by column name, e.g. the readings at the peak temperature
+    ///
+    /// Fails with `ParameterNotFound` if no column measures the parameter, returns an empty
+    /// object if the column has no readings.
+    pub fn row_at_extreme(
+        &self,
+        parameter: Parameter,
+        which: Extreme,
+    ) -> Result<Map<String, Value>, AquaTrollLogError> {
+        let Some(index) = self.log_data.row_at_extreme(parameter, which)? else {
+            return Ok(Map::new());
+        };
+        let row = self.rows().nth(index).unwrap_or_default();
+        Ok(self.log_data.columns.iter().cloned().zip(row).collect())
+    }
+
+    /// Write `log_data` to `<stem>_data.csv`, `log_note` to `<stem>_note.csv` (if present) and
+    /// `attr` to `<stem>_attr.json` in `dir`
+    pub fn export_bundle(&self, dir: &Path, stem: &str) -> Result<(), AquaTrollLogError> {
+        let json_file = File::create(dir.join(format!("{stem}_attr.json")))?;
+        serde_json::to_writer_pretty(json_file, &self.attr)?;
+
+        if let Some(ref log_note) = self.log_note {
+            log_note.write_csv(File::create(dir.join(format!("{stem}_note.csv")))?)?;
+        }
+
+        self.log_data
+            .write_csv(File::create(dir.join(format!("{stem}_data.csv")))?)?;
+
+        Ok(())
+    }
+
+    /// Write `log_data` as CSV with local timestamps of the `tz` time zone
+    ///
+    /// Timestamps are formatted with `format`, `%Y-%m-%d %H:%M:%S` by default.
+    pub fn to_csv<W: std::io::Write, Tz: TimeZone>(
+        &self,
+        writer: W,
+        tz: &Tz,
+        format: Option<&str>,
+    ) -> Result<(), AquaTrollLogError>
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        self.log_data
+            .write_csv_in_timezone(writer, tz, format.unwrap_or("%Y-%m-%d %H:%M:%S"))?;
+        Ok(())
+    }
+
+    /// Sampling interval of the `Sample Rate` attribute of TXT logs, `None` if the attribute is
+    /// absent or malformed
+    pub fn sample_interval(&self) -> Option<Duration> {
+        std::iter::once(&self.attr)
+            .chain(self.attr.values().filter_map(Value::as_object))
+            .find_map(|section| section.get("Sample Rate"))
+            .and_then(Value::as_str)
+            .and_then(parse_sample_rate)
+    }
+
+    /// Logging mode of the `Log Configuration` attributes of TXT logs, `None` if unknown
+    ///
+    /// Gaps of `time_gaps` are expected of logs of event mode, but anomalous of linear modes.
+    pub fn logging_mode(&self) -> Option<LoggingMode> {
+        LoggingMode::from_attr(&self.attr)
+    }
+
+    /// Find gaps between consecutive readings spaced more than the sampling interval plus
+    /// `tolerance` apart
+    ///
+    /// The sampling interval is the median interval of readings. Gaps are returned as
+    /// `(start, end, duration)`.
+    pub fn time_gaps(&self, tolerance: Duration) -> Vec<(NaiveDateTime, NaiveDateTime, Duration)> {
+        let timestamps = self.log_data.timestamps();
+        let mut intervals: Vec<Duration> = timestamps
+            .windows(2)
+            .filter_map(|w| (w[1] - w[0]).to_std().ok())
+            .collect();
+        if intervals.is_empty() {
+            return vec![];
+        }
+        intervals.sort();
+
+        self.find_gaps(intervals[intervals.len() / 2], tolerance)
+    }
+
+    /// Find gaps between consecutive readings spaced more than `expected` plus `tolerance`
+    /// apart, e.g. of the `sample_interval`
+    ///
+    /// Readings are assumed in ascending order, consecutive readings out of order or of
+    /// duplicate timestamps are not gaps. Gaps are returned as `(start, end, duration)`.
+    pub fn find_gaps(
+        &self,
+        expected: Duration,
+        tolerance: Duration,
+    ) -> Vec<(NaiveDateTime, NaiveDateTime, Duration)> {
+        let threshold = expected + tolerance;
+
+        self.log_data
+            .timestamps()
+            .windows(2)
+            .filter_map(|w| {
+                let gap = (w[1] - w[0]).to_std().ok()?;
+                (gap > threshold).then_some((w[0], w[1], gap))
+            })
+            .collect()
+    }
+
+    /// Concatenate logs into one with the attributes of the first log
+    ///
+    /// Log data of a parameter reported in different units are handled by `policy`. Data
+    /// quality codes are concatenated if any log has them, `None` of logs without.
+    /// Timestamps are converted into the time zone of the first log, mixing logs of known
+    /// and unknown time zones is recorded as a `MixedTimeZones` warning.
+    pub fn concat(
+        logs: &[AquaTrollLogData],
+        policy: UnitPolicy,
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let Some(first) = logs.first() else {
+            return Err(AquaTrollLogError::InvalidData);
+        };
+        let log_notes: Vec<Table> = logs.iter().filter_map(|l| l.log_note.clone()).collect();
+        let log_data: Vec<Table> = logs.iter().map(|l| l.log_data.clone()).collect();
+        let mut warnings: Vec<ReadWarning> = logs.iter().flat_map(|l| l.warnings.clone()).collect();
+        if logs
+            .iter()
+            .any(|l| l.log_data.timezone.is_some() != first.log_data.timezone.is_some())
+        {
+            warnings.push(ReadWarning::MixedTimeZones);
+        }
+        let log_quality = logs
+            .iter()
+            .any(|l| l.log_quality.is_some())
+            .then(|| {
+                let qualities: Vec<_> = logs
+                    .iter()
+                    .map(|l| (&l.log_data, l.log_quality.as_ref()))
+                    .collect();
+                QualityTable::concat(&qualities)
+            })
+            .transpose()?;
+
+        Ok(AquaTrollLogData {
+            attr: first.attr.clone(),
+            log_note: (!log_notes.is_empty())
+                .then(|| Table::concat(&log_notes, policy))
+                .transpose()?,
+            log_data: Table::concat(&log_data, policy)?,
+            log_quality,
+            warnings,
+        })
+    }
+
+    /// Merge logs of the same schema, e.g. daily exports of a logger, into one continuous log
+    ///
+    /// Log data of the logs must have the same columns, in the same order and of the same
+    /// column metadata. The attributes of the first log are taken, with the number of logs
+    /// merged as `Merged Logs`.
+    pub fn merge(logs: &[AquaTrollLogData]) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let Some(first) = logs.first() else {
+            return Err(AquaTrollLogError::InvalidData);
+        };
+        let expected = &first.log_data;
+        for log in &logs[1..] {
+            let table = &log.log_data;
+            let mismatch =
+                (0..usize::max(expected.num_columns(), table.num_columns())).find(|&i| {
+                    expected.columns.get(i) != table.columns.get(i)
+                        || expected.column_metadata.get(i) != table.column_metadata.get(i)
+                });
+            if let Some(i) = mismatch {
+                let name = expected.columns.get(i).or(table.columns.get(i));
+                return Err(AquaTrollLogError::SchemaMismatch(
+                    name.cloned().unwrap_or_default(),
+                ));
+            }
+        }
+
+        let mut log = AquaTrollLogData::concat(logs, UnitPolicy::Error)?;
+        log.attr
+            .insert("Merged Logs".to_string(), Value::from(logs.len()));
+        Ok(log)
+    }
+
+    /// Apply all the cleanup steps of `NormalizeOptions` to a copy of the log
+    pub fn normalize(&self) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        self.normalize_with(&NormalizeOptions::default())
+    }
+
+    /// Apply the enabled cleanup steps to a copy of the log
+    ///
+    /// Steps run in the order sort, dedup, drop null columns, normalize column names. With
+    /// `attach_metadata` the `Normalized` attribute records the record count, time range,
+    /// the number of removed duplicate rows and the names of dropped columns.
+    pub fn normalize_with(
+        &self,
+        options: &NormalizeOptions,
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let mut log_data = self.log_data.clone();
+        let mut log_quality = self.log_quality.clone();
+
+        if options.sort_by_time {
+            let order = log_data.time_order();
+            log_data.select_rows(&order);
+            if let Some(ref mut log_quality) = log_quality {
+                log_quality.select_rows(&order);
+            }
+        }
+        let mut num_duplicates = 0;
+        if options.dedup_timestamps {
+            let rows = log_data.unique_time_rows();
+            num_duplicates = log_data.num_rows() - rows.len();
+            log_data.select_rows(&rows);
+            if let Some(ref mut log_quality) = log_quality {
+                log_quality.select_rows(&rows);
+            }
+        }
+        let dropped_columns = if options.drop_null_columns {
+            let keep = log_data.non_null_columns();
+            if let Some(ref mut log_quality) = log_quality {
+                log_quality.retain_columns(&keep);
+            }
+            log_data.retain_columns(&keep)
+        } else {
+            vec![]
+        };
+        if options.normalize_column_names {
+            log_data.normalize_column_names();
+            if let Some(ref mut log_quality) = log_quality {
+                log_quality.columns = log_data.columns.clone();
+            }
+        }
+
+        let mut attr = self.attr.clone();
+        if options.attach_metadata {
+            let timestamps = log_data.timestamps();
+            let time_value = |dt: Option<&NaiveDateTime>| {
+                dt.map(|dt| Value::String(dt.format("%Y-%m-%dT%H:%M:%S").to_string()))
+                    .unwrap_or(Value::Null)
+            };
+            let summary = Map::from_iter([
+                ("Record Count".to_string(), log_data.num_rows().into()),
+                (
+                    "Start Time".to_string(),
+                    time_value(timestamps.iter().min()),
+                ),
+                ("End Time".to_string(), time_value(timestamps.iter().max())),
+                ("Duplicate Rows".to_string(), num_duplicates.into()),
+                ("Dropped Columns".to_string(), dropped_columns.into()),
+            ]);
+            attr.insert("Normalized".to_string(), Value::Object(summary));
+        }
+
+        Ok(AquaTrollLogData {
+            attr,
+            log_note: self.log_note.clone(),
+            log_data,
+            log_quality,
+            warnings: self.warnings.clone(),
+        })
+    }
+
+    /// Convert `log_data` into an Arrow record batch
+    ///
+    /// `attr` is flattened into the schema metadata under dotted `attr.` keys with JSON values,
+    /// e.g. `attr.Log Data.Record Count`, see `attr_from_schema` for the inverse.
+    #[cfg(feature = "arrow")]
+    pub fn to_record_batch(&self) -> Result<arrow_array::RecordBatch, AquaTrollLogError> {
+        let batch = table_to_record_batch(&self.log_data)?;
+        let schema = batch
+            .schema()
+            .as_ref()
+            .clone()
+            .with_metadata(flatten_attr(&self.attr));
+        Ok(arrow_array::RecordBatch::try_new(
+            std::sync::Arc::new(schema),
+            batch.columns().to_vec(),
+        )?)
+    }
+
+    /// Rebuild the nested attributes flattened into the schema metadata by `to_record_batch`,
+    /// e.g. of a record batch read back from Parquet or Arrow IPC
+    #[cfg(feature = "arrow")]
+    pub fn attr_from_schema(
+        schema: &arrow_schema::Schema,
+    ) -> Result<Map<String, Value>, AquaTrollLogError> {
+        unflatten_attr(schema.metadata().iter())
+    }
+
+    /// Convert `log_data` into a Polars data frame, timestamps as `Datetime` of milliseconds
+    #[cfg(feature = "polars")]
+    pub fn to_polars(&self) -> Result<polars::frame::DataFrame, AquaTrollLogError> {
+        table_to_data_frame(&self.log_data)
+    }
+
+    /// Convert `log_note` into a Polars data frame, `None` of logs without notes
+    #[cfg(feature = "polars")]
+    pub fn note_to_polars(&self) -> Result<Option<polars::frame::DataFrame>, AquaTrollLogError> {
+        self.log_note.as_ref().map(table_to_data_frame).transpose()
+    }
+
+    /// Convert `log_data` into an Arrow schema and column arrays, without the record batch
+    #[cfg(feature = "arrow")]
+    pub fn to_arrow(
+        &self,
+    ) -> Result<(arrow_schema::SchemaRef, Vec<arrow_array::ArrayRef>), AquaTrollLogError> {
+        let batch = self.to_record_batch()?;
+        Ok((batch.schema(), batch.columns().to_vec()))
+    }
+
+    /// Copy of the log with `log_data` projected to the columns named in `names`, in the order of
+    /// the log, e.g. before writing large logs as CSV or Parquet
+    ///
+    /// The timestamp column is always kept. Fails with `UnknownColumn` of a name not in the
+    /// log data.
+    pub fn select(&self, names: &[&str]) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        if let Some(name) = names
+            .iter()
+            .find(|name| !self.log_data.columns.iter().any(|c| c == *name))
+        {
+            return Err(AquaTrollLogError::UnknownColumn(name.to_string()));
+        }
+
+        let keep: Vec<bool> = self
+            .log_data
+            .columns
+            .iter()
+            .map(|name| names.contains(&name.as_str()))
+            .collect();
+        Ok(self.retain_columns(&keep))
+    }
+
+    /// Copy of the log with `log_data` projected to the columns whose parameter, unit and
+    /// sensor satisfy `predicate`, in the order of the log, e.g. of all voltage readings
+    ///
+    /// The timestamp column is always kept.
+    pub fn select_where(&self, predicate: impl Fn(&ColumnDescriptor) -> bool) -> AquaTrollLogData {
+        let keep: Vec<bool> = self
+            .log_data
+            .column_descriptors()
+            .iter()
+            .map(predicate)
+            .collect();
+        self.retain_columns(&keep)
+    }
+
+    /// Copy of the log with the columns of `log_data` flagged in `keep` and the timestamp
+    fn retain_columns(&self, keep: &[bool]) -> AquaTrollLogData {
+        let mut log_data = self.log_data.clone();
+        let datetime = log_data.datetime_column();
+        let keep: Vec<bool> = keep
+            .iter()
+            .enumerate()
+            .map(|(i, keep)| *keep || Some(i) == datetime)
+            .collect();
+        log_data.retain_columns(&keep);
+        let mut log_quality = self.log_quality.clone();
+        if let Some(ref mut log_quality) = log_quality {
+            log_quality.retain_columns(&keep);
+        }
+
+        AquaTrollLogData {
+            attr: self.attr.clone(),
+            log_note: self.log_note.clone(),
+            log_data,
+            log_quality,
+            warnings: self.warnings.clone(),
+        }
+    }
+
+    /// Copy of the log with rows of `log_data` and `log_note` of timestamps from `start`
+    /// (inclusive) to `end` (exclusive), e.g. of a deployment window
+    ///
+    /// Timestamps are compared in the time zone of the log, taken as UTC if unknown. Rows
+    /// without a timestamp are dropped, windows of no rows give empty tables.
+    pub fn filter_time_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> AquaTrollLogData {
+        let mut log_data = self.log_data.clone();
+        let rows = log_data.time_range_rows(start, end);
+        log_data.select_rows(&rows);
+        let mut log_quality = self.log_quality.clone();
+        if let Some(ref mut log_quality) = log_quality {
+            log_quality.select_rows(&rows);
+        }
+        let log_note = self.log_note.clone().map(|mut log_note| {
+            let rows = log_note.time_range_rows(start, end);
+            log_note.select_rows(&rows);
+            log_note
+        });
+
+        AquaTrollLogData {
+            attr: self.attr.clone(),
+            log_note,
+            log_data,
+            log_quality,
+            warnings: self.warnings.clone(),
+        }
+    }
+
+    /// Copy of the log with depth and level readings of `log_data` set to null in rows of the
+    /// sensor out of water, as of `Table::mask_out_of_water`
+    pub fn mask_out_of_water(
+        &self,
+        reference: PressureReference,
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let mut log_data = self.log_data.clone();
+        log_data.mask_out_of_water(reference)?;
+
+        Ok(AquaTrollLogData {
+            attr: self.attr.clone(),
+            log_note: self.log_note.clone(),
+            log_data,
+            log_quality: self.log_quality.clone(),
+            warnings: self.warnings.clone(),
+        })
+    }
+
+    /// Copy of the log with null readings of `log_data` filled by `strategy`
+    pub fn fill_missing(&self, strategy: FillStrategy) -> AquaTrollLogData {
+        let mut log_data = self.log_data.clone();
+        log_data.fill_missing(strategy);
+
+        AquaTrollLogData {
+            attr: self.attr.clone(),
+            log_note: self.log_note.clone(),
+            log_data,
+            log_quality: self.log_quality.clone(),
+            warnings: self.warnings.clone(),
+        }
+    }
+
+    /// Write `log_data` in Parquet format
+    ///
+    /// `attr` and `log_note` are stored as JSON strings in the key-value file metadata under
+    /// the `attr` and `log_note` keys, `attr` is also flattened into the Arrow schema metadata
+    /// as of `to_record_batch`.
+    #[cfg(feature = "parquet")]
+    pub fn to_parquet<W: Write + Seek + Send>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), AquaTrollLogError> {
+        use parquet::{
+            arrow::ArrowWriter, file::metadata::KeyValue, file::properties::WriterProperties,
+        };
+
+        let batch = self.to_record_batch()?;
+
+        let mut metadata = vec![KeyValue::new(
+            "attr".to_string(),
+            serde_json::to_string(&self.attr)?,
+        )];
+        if let Some(ref log_note) = self.log_note {
+            metadata.push(KeyValue::new(
+                "log_note".to_string(),
+                serde_json::to_string(log_note)?,
+            ));
+        }
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(metadata))
+            .build();
+
+        let mut arrow_writer = ArrowWriter::try_new(writer, batch.schema(), Some(props))?;
+        arrow_writer.write(&batch)?;
+        arrow_writer.close()?;
+
+        Ok(())
+    }
+}
+
+impl AquaTrollLogData {
+    /// Apply the read options taking effect after parsing
+    fn with_read_options(self, options: &ReadOptions) -> Result<Self, AquaTrollLogError> {
+        let mut log = self.split_interleaved_notes(options);
+        if log.log_data.num_rows() == 0 && !options.allow_empty {
+            return Err(AquaTrollLogError::EmptyTable);
+        }
+        for (column, unit) in log.log_data.unknown_units() {
+            log.warnings.push(ReadWarning::UnknownUnit { column, unit });
+        }
+
+        let mut renames = HashMap::new();
+        if let Some(ref unit) = options.temperature_unit {
+            renames = log.log_data.convert_temperatures(unit)?;
+            if let Some(ref mut log_quality) = log.log_quality {
+                log_quality.rename_columns(&renames);
+            }
+        }
+
+        if options.compact_column_names {
+            let compact = log.log_data.compact_column_names();
+            if let Some(ref mut log_quality) = log.log_quality {
+                log_quality.rename_columns(&compact);
+            }
+            // Names of converted temperature columns are renamed from their names in the log
+            for (old, new) in compact {
+                match renames.values_mut().find(|name| **name == old) {
+                    Some(name) => *name = new,
+                    None => {
+                        renames.insert(old, new);
+                    }
+                }
+            }
+        }
+
+        if !options.column_renames.is_empty() {
+            // Names in the log of converted temperature columns are renamed by their new names
+            let column_renames: HashMap<String, String> = options
+                .column_renames
+                .iter()
+                .map(|(old, new)| (renames.get(old).unwrap_or(old).clone(), new.clone()))
+                .collect();
+            for name in log.log_data.rename_columns(&column_renames) {
+                log.warnings.push(ReadWarning::RenamedColumnNotFound(name));
+            }
+            if let Some(ref mut log_quality) = log.log_quality {
+                log_quality.rename_columns(&column_renames);
+            }
+        }
+
+        if options.downcast_integer_columns {
+            log.log_data.downcast_integer_columns();
+        }
+        Ok(log)
+    }
+
+    /// Move note rows interleaved with the log data into the log note
+    fn split_interleaved_notes(mut self, options: &ReadOptions) -> Self {
+        let Some((column, value)) = &options.note_discriminator else {
+            return self;
+        };
+        let mut log_data = self.log_data.clone();
+        let Some(notes) = log_data.split_notes(column, value) else {
+            return self;
+        };
+
+        match &mut self.log_note {
+            Some(log_note) if log_note.columns == notes.columns => {
+                log_note.rows.extend(notes.rows);
+                log_note.sort_by_time();
+            }
+            // Rows of the notes are left in the log data
+            Some(_) => {
+                self.warnings
+                    .push(ReadWarning::NoteColumnsMismatch(notes.columns));
+                return self;
+            }
+            None => self.log_note = Some(notes),
+        }
+        self.log_data = log_data;
+        self
+    }
 }
 
 impl Serialize for AquaTrollLogData {
@@ -62,26 +920,242 @@ impl Serialize for AquaTrollLogData {
     }
 }
 
+/// Reader of log files, configured by chaining the `with_*` setters of the options before
+/// reading, e.g. `AquaTrollLogReader::default().with_timezone(tz).read_txt(&mut file)`
+///
+/// Options apply to every read of the reader, options not applicable to a format are ignored.
+/// There's no separate builder type: the setters consume and return the reader, and reads
+/// borrow it, so a reader configured once reads any number of files.
 #[derive(Default)]
 pub struct AquaTrollLogReader {
-    datetime_parser: DateTimeParser,
+    options: ReadOptions,
 }
 
 impl AquaTrollLogReader {
-    // TODO: Add troll calibration file reader
     // TODO: Check and convert unit of table data by numbat
 
     pub fn new(datetime_parser: DateTimeParser) -> Self {
-        Self { datetime_parser }
+        Self {
+            options: ReadOptions {
+                datetime_parser,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Only read columns of sensors with the given serial numbers (TXT and HTML logs)
+    pub fn with_sensor_filter(mut self, serials: &[u64]) -> Self {
+        self.options.sensor_filter = Some(serials.to_vec());
+        self
+    }
+
+    /// Set the time zone of timestamps, instead of the one detected from the log
+    ///
+    /// Time zones are detected from the `Time Zone` of TXT logs and the `Time Offset` of HTML
+    /// logs, CSV logs carry no time zone.
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.options.timezone = Some(timezone);
+        self
+    }
+
+    /// Parse values of attributes into JSON numbers, booleans and RFC 3339 date times when they
+    /// look like one (TXT and HTML logs), values are kept as strings by default
+    ///
+    /// HTML members of machine readable values are also given as `<name> (ms)` integers of
+    /// `isi-timespan-milliseconds` (e.g. `Duration (ms)`), and `<name> (UTC)` date times of
+    /// `isi-timestamp` ticks (e.g. `Start Time (UTC)`).
+    pub fn with_typed_attrs(mut self, typed_attrs: bool) -> Self {
+        self.options.typed_attrs = typed_attrs;
+        self
+    }
+
+    /// Read infinite readings of sensor faults (e.g. `Inf`, `1.#INF` or overflows like `1e999`)
+    /// as null instead of infinity
+    pub fn with_infinite_as_null(mut self, infinite_as_null: bool) -> Self {
+        self.options.infinite_as_null = infinite_as_null;
+        self
+    }
+
+    /// Read notes interleaved with the log data by timestamp into the log note (CSV and TXT
+    /// logs)
+    ///
+    /// Rows with `note_value` in the `column` are taken as notes, the `column` and `Note`
+    /// columns are then removed from the log data.
+    pub fn with_note_discriminator(mut self, column: &str, note_value: &str) -> Self {
+        self.options.note_discriminator = Some((column.to_string(), note_value.to_string()));
+        self
+    }
+
+    /// Read rows of TXT tables which don't span all columns, e.g. of partially corrupt logs,
+    /// instead of failing with `ColumnCountMismatch`
+    ///
+    /// Short rows are then reported in the `warnings` of the log.
+    pub fn with_lenient_columns(mut self, lenient: bool) -> Self {
+        self.options.lenient_columns = lenient;
+        self
+    }
+
+    /// Fail with `RecordCountMismatch` if the `Record Count` of a TXT log doesn't match the
+    /// rows read, e.g. of truncated files, instead of reporting it in the `warnings` of the log
+    pub fn with_strict_record_count(mut self, strict: bool) -> Self {
+        self.options.strict_record_count = strict;
+        self
+    }
+
+    /// Read the log data columns with the given names as the given types instead of the types
+    /// inferred from the names, e.g. a `Site ID` column as text
+    pub fn with_column_types(mut self, column_types: HashMap<String, ColumnType>) -> Self {
+        self.options.column_types = column_types;
+        self
+    }
+
+    /// Recover TXT rows of a known export bug, where the timestamp and the first reading share
+    /// a column when the reading is wide, by splitting off the timestamp
+    pub fn with_recovery(mut self, recovery: bool) -> Self {
+        self.options.recovery = recovery;
+        self
+    }
+
+    /// Name the timestamp column of the log data and log note as `name`, e.g. `timestamp`,
+    /// instead of `DateTime`
+    pub fn with_timestamp_column_name(mut self, name: &str) -> Self {
+        self.options.datetime_column_name = Some(name.to_string());
+        self
+    }
+
+    /// Read timestamps of HTML logs from the `isi-timestamp` device ticks of the data rows, for
+    /// sub-second precision of logs sampled faster than once a second
+    ///
+    /// Ticks count 1/65536 seconds since the Unix epoch in UTC, and are converted into the time
+    /// zone of the log (UTC if unknown). Rows without ticks keep the text timestamp.
+    pub fn with_tick_timestamps(mut self, tick_timestamps: bool) -> Self {
+        self.options.tick_timestamps = tick_timestamps;
+        self
+    }
+
+    /// Skip the log notes of TXT logs without parsing them, for reading only the log data,
+    /// `log_note` is left `None`
+    pub fn with_skip_note(mut self, skip_note: bool) -> Self {
+        self.options.skip_note = skip_note;
+        self
+    }
+
+    /// Read logs of a data header but no data rows into an empty log data table, instead of
+    /// failing with `EmptyTable`
+    pub fn with_allow_empty(mut self, allow_empty: bool) -> Self {
+        self.options.allow_empty = allow_empty;
+        self
+    }
+
+    /// Match CSV headers against the timestamp and text header names (`Date/Time`, `Note`,
+    /// ...) ignoring surrounding whitespace and case, e.g. of `date/time ` headers
+    pub fn with_lenient_header_match(mut self, lenient: bool) -> Self {
+        self.options.lenient_header_match = lenient;
+        self
+    }
+
+    /// Set the delimiter and decimal mark of CSV files, and the handling of rows of a different
+    /// length than the header
+    pub fn with_csv_options(mut self, csv_options: CsvReadOptions) -> Self {
+        self.options.csv = csv_options;
+        self
+    }
+
+    /// Convert readings of temperature columns into `unit`, e.g. `Unit::Celsius` for logs of
+    /// instruments set to Fahrenheit
+    ///
+    /// Source units are taken from the column names, which are renamed to the new unit.
+    pub fn normalize_temperature_to(mut self, unit: Unit) -> Self {
+        self.options.temperature_unit = Some(unit);
+        self
+    }
+
+    /// Rename log data columns by the `renames` map of names in the log to new names
+    ///
+    /// Columns are renamed after parsing, names not found in the log are warned of.
+    pub fn with_column_rename_map(mut self, renames: HashMap<String, String>) -> Self {
+        self.options.column_renames = renames;
+        self
+    }
+
+    /// Name log data columns of known parameters by the short names of the parameters, e.g.
+    /// `RhodWT_FI (RFU)` of `Rhodamine WT Fluorescence Intensity (RFU)`, the long names of the
+    /// log are kept by default
+    ///
+    /// Renames by `with_column_rename_map` still take the names in the log.
+    pub fn with_compact_column_names(mut self, compact: bool) -> Self {
+        self.options.compact_column_names = compact;
+        self
+    }
+
+    /// Append the sensor serial to the names of TXT log data columns, e.g. `pH (pH) (999991)`
+    /// as of the column headers of HTML logs, the bare names are kept by default
+    ///
+    /// Serials are taken from the `SN#:` lines of the data header, columns without a serial
+    /// keep their names.
+    pub fn with_serial_column_names(mut self, serial_column_names: bool) -> Self {
+        self.options.serial_column_names = serial_column_names;
+        self
+    }
+
+    /// Read float log data columns of only integral values, e.g. of counts, as integer columns,
+    /// floats are kept by default
+    pub fn with_downcast_integer_columns(mut self, downcast: bool) -> Self {
+        self.options.downcast_integer_columns = downcast;
+        self
+    }
+
+    /// Read cells of HTML logs flagged with a data quality below `quality` as null
+    pub fn with_null_below_quality(mut self, quality: DataQuality) -> Self {
+        self.options.null_below_quality = Some(quality);
+        self
+    }
+
+    /// Decode TXT and CSV logs as `encoding`, instead of the encoding detected from the file
+    pub fn with_encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.options.encoding = Some(encoding);
+        self
     }
 
+    /// Read CSV log file of the encoding detected from the BOM, UTF-8 if valid, ISO-8859-3
+    /// otherwise, unless set by `with_encoding`
     pub fn read_csv<R: Read + Seek>(
         &self,
         reader: &mut R,
     ) -> Result<AquaTrollLogData, AquaTrollLogError> {
-        let mut reader = decode_reader(reader, ISO_8859_3)?;
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        let encoding = self.options.encoding.unwrap_or_else(|| csv_encoding(&buf));
+
+        self.read_csv_with_encoding(&mut Cursor::new(buf), encoding)
+    }
+
+    /// Read CSV log file of the given encoding, e.g. `Encoding::for_label(b"windows-1252")`
+    pub fn read_csv_with_encoding<R: Read>(
+        &self,
+        reader: &mut R,
+        encoding: &'static Encoding,
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let mut reader = decode_reader(reader, encoding)?;
+        self.read_decoded_csv(&mut reader)
+    }
 
-        let log_data = match read_csv_table(&mut reader, &self.datetime_parser) {
+    /// Read a CSV log file already decoded into a string
+    pub fn read_csv_str(&self, content: &str) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        self.read_decoded_csv(&mut Cursor::new(strip_bom(content).as_bytes()))
+    }
+
+    /// Read a CSV log file in memory, e.g. downloaded, as of `read_csv`
+    pub fn read_csv_bytes(&self, content: &[u8]) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        self.read_csv(&mut Cursor::new(content))
+    }
+
+    fn read_decoded_csv<R: BufRead + Seek>(
+        &self,
+        reader: &mut R,
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let mut warnings = vec![];
+        let log_data = match read_csv_table(reader, &self.options, &mut warnings) {
             Ok(data) => data,
             Err(AquaTrollLogError::WithCsvPartialResult(part_result)) => {
                 return Err(ErrorWithPartialResult {
@@ -89,6 +1163,8 @@ impl AquaTrollLogReader {
                         attr: Map::new(),
                         log_note: None,
                         log_data: *part_result.result,
+                        log_quality: None,
+                        warnings,
                     }),
                     errors: part_result.errors,
                 }
@@ -97,58 +1173,409 @@ impl AquaTrollLogReader {
             Err(e) => return Err(e),
         };
 
-        Ok(AquaTrollLogData {
+        AquaTrollLogData {
             attr: Map::new(),
             log_note: None,
             log_data,
-        })
+            log_quality: None,
+            warnings,
+        }
+        .with_read_options(&self.options)
     }
 
-    /// Read TXT log file (UTF-16LE encoded, exported from WinSitu)
+    /// Read TXT log file exported from WinSitu, UTF-16LE, UTF-8 or Windows-1252 (of legacy
+    /// exports) encoded
+    ///
+    /// A `Record Count` of the log data not matching the rows read, e.g. of truncated files, is
+    /// reported in the `warnings` of the log. Attribute sections after the log data, e.g. of a
+    /// trailing summary, are read under the `Summary` key of `attr`.
     pub fn read_txt<R: Read + Seek>(
         &self,
         reader: &mut R,
     ) -> Result<AquaTrollLogData, AquaTrollLogError> {
-        let mut reader = decode_reader(reader, UTF_16LE)?;
+        let mut reader = decode_txt_reader(reader, self.options.encoding)?;
+        self.read_txt_log(&mut reader)
+    }
 
-        let mut attr = Map::new();
-        read_attr(&mut reader, &mut attr, true)?;
-        let log_note = read_table(&mut reader, &self.datetime_parser)?;
-        let log_data_attr = read_log_data_attr(&mut reader)?;
-        attr.insert("Log Data".to_string(), Value::Object(log_data_attr));
-        let log_data = read_table(&mut reader, &self.datetime_parser)?;
+    /// Read a TXT log file already decoded into a string
+    pub fn read_txt_str(&self, content: &str) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        self.read_txt_log(&mut Cursor::new(strip_bom(content).as_bytes()))
+    }
 
-        Ok(AquaTrollLogData {
-            attr,
-            log_note: Some(log_note),
-            log_data,
-        })
+    /// Read a TXT log file in memory, e.g. downloaded, as of `read_txt`
+    pub fn read_txt_bytes(&self, content: &[u8]) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        self.read_txt(&mut Cursor::new(content))
+    }
+
+    /// Read a decoded TXT log file of a single session, attribute sections after the log data
+    /// are read into `attr` under the `Summary` key
+    fn read_txt_log<R: BufRead + Seek>(
+        &self,
+        reader: &mut R,
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let mut log = self.read_txt_session(reader)?;
+        if let Some(summary) = self.read_txt_summary(reader)? {
+            log.attr
+                .insert("Summary".to_string(), Value::Object(summary));
+        }
+        Ok(log)
+    }
+
+    /// Read the attribute sections of a summary after the log data of a session
+    ///
+    /// Reading stops at the start of another session, the attributes followed by its
+    /// `Log Notes` or `Log Data` are left unread. Returns `None` if there are no sections.
+    fn read_txt_summary<R: BufRead + Seek>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Option<Map<String, Value>>, AquaTrollLogError> {
+        let is_session_start = |line: &str| line == "Log Notes:" || line == "Log Data:";
+        let mut summary = Map::new();
+        loop {
+            let start = reader.stream_position()?;
+            if peek_content_line(reader)?.is_none_or(|line| is_session_start(&line)) {
+                break;
+            }
+            let mut section = Map::new();
+            read_attr(reader, &mut section, true, &self.options)?;
+            if peek_content_line(reader)?.is_some_and(|line| is_session_start(&line)) {
+                reader.seek(SeekFrom::Start(start))?;
+                break;
+            }
+            summary.extend(section);
+        }
+        Ok((!summary.is_empty()).then_some(summary))
+    }
+
+    /// Read every log session of a TXT log file, in file order
+    ///
+    /// Some dumps concatenate several log sessions separated by section breaks. A session may
+    /// repeat the attributes and log notes, or be a bare `Log Data` section sharing the
+    /// attributes of the previous session. Attribute sections after the last session, e.g. of
+    /// a trailing summary, are read into `attr` of the last session under the `Summary` key.
+    pub fn read_txt_multi<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Vec<AquaTrollLogData>, AquaTrollLogError> {
+        let mut reader = decode_txt_reader(reader, self.options.encoding)?;
+        let mut logs = vec![self.read_txt_session(&mut reader)?];
+
+        while let Some(line) = peek_content_line(&mut reader)? {
+            let log = if line == "Log Data:" {
+                let attr = logs.last().map(|log| log.attr.clone()).unwrap_or_default();
+                self.read_txt_log_data(&mut reader, attr, None, vec![])?
+            } else if let Some(summary) = self.read_txt_summary(&mut reader)? {
+                if let Some(log) = logs.last_mut() {
+                    log.attr
+                        .insert("Summary".to_string(), Value::Object(summary));
+                }
+                continue;
+            } else {
+                self.read_txt_session(&mut reader)?
+            };
+            logs.push(log);
+        }
+
+        Ok(logs)
     }
 
+    /// Read attributes, log notes and log data of a decoded TXT log session
+    fn read_txt_session<R: BufRead + Seek>(
+        &self,
+        reader: &mut R,
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let mut attr = Map::new();
+        read_attr(reader, &mut attr, true, &self.options)?;
+        if self.options.skip_note {
+            // Lines of the log notes are skipped until the `Log Data` section
+            return self.read_txt_log_data(reader, attr, None, vec![]);
+        }
+        let mut warnings = vec![];
+        let log_note = read_table(reader, &self.options, &mut warnings)?;
+        self.read_txt_log_data(reader, attr, Some(log_note), warnings)
+    }
+
+    /// Read the `Log Data` section of a decoded TXT log session
+    fn read_txt_log_data<R: BufRead>(
+        &self,
+        reader: &mut R,
+        mut attr: Map<String, Value>,
+        mut log_note: Option<Table>,
+        mut warnings: Vec<ReadWarning>,
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let log_data_attr = read_log_data_attr(reader)?;
+        let record_count = log_data_attr.get("Record Count").and_then(Value::as_u64);
+        let timezone = self.options.timezone.or_else(|| {
+            log_data_attr
+                .get("Time Zone")
+                .and_then(Value::as_str)
+                .and_then(parse_time_zone)
+        });
+        attr.insert("Log Data".to_string(), Value::Object(log_data_attr));
+        let mut log_data = read_table(reader, &self.options, &mut warnings)?;
+        if let Some(expected) = record_count.map(|count| count as usize) {
+            let found = log_data.num_rows();
+            if expected != found && self.options.strict_record_count {
+                return Err(AquaTrollLogError::RecordCountMismatch { expected, found });
+            } else if expected != found {
+                warnings.push(ReadWarning::RecordCountMismatch { expected, found });
+            }
+        }
+        // Offsets of time zone abbreviations of the timestamps take precedence
+        if let Some(ref mut log_note) = log_note {
+            log_note.timezone = log_note.timezone.or(timezone);
+        }
+        log_data.timezone = log_data.timezone.or(timezone);
+
+        AquaTrollLogData {
+            attr,
+            log_note,
+            log_data,
+            log_quality: None,
+            warnings,
+        }
+        .with_read_options(&self.options)
+    }
+
+    /// Stream rows of the log data of a TXT log file
+    ///
+    /// Unlike `read_txt`, the file is decoded and parsed lazily one line at a time, so memory
+    /// use stays flat regardless of the file size. The encoding is detected from the BOM or
+    /// the head of the file (64 KiB), unless set by `with_encoding`. Log notes and attributes
+    /// other than the `Log Data` section are skipped.
+    pub fn stream_txt_rows<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<TxtRows<impl BufRead>, AquaTrollLogError> {
+        TxtRows::new(
+            decode_txt_buf_reader(reader, self.options.encoding)?,
+            &self.options,
+        )
+    }
+
+    /// Read the columns of the log data table of a TXT log file as interpreted from its dash
+    /// separator line, for diagnosing logs of unexpected layouts
+    pub fn inspect_txt_layout<R: Read>(
+        &self,
+        mut reader: R,
+    ) -> Result<Vec<TxtColumnSpan>, AquaTrollLogError> {
+        let mut reader = decode_txt_reader(&mut reader, self.options.encoding)?;
+        read_log_data_attr(&mut reader)?;
+        read_table_layout(&mut reader)
+    }
+
+    /// Read gzip compressed TXT log file
+    #[cfg(feature = "flate2")]
+    pub fn read_gzipped_txt<R: Read>(
+        &self,
+        reader: &mut R,
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        let mut buf = Vec::new();
+        flate2::read::GzDecoder::new(reader)
+            .read_to_end(&mut buf)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => AquaTrollLogError::UnexpectedEof,
+                _ => e.into(),
+            })?;
+
+        self.read_txt(&mut Cursor::new(buf))
+    }
+
+    /// Read a HTML log file
     pub fn read_html<R: Read>(
         &self,
         reader: &mut R,
     ) -> Result<AquaTrollLogData, AquaTrollLogError> {
-        let (attr, log_data) = read_html(reader, &self.datetime_parser)?;
+        let (attr, log_data, log_quality, warnings) = read_html(reader, &self.options)?;
 
-        Ok(AquaTrollLogData {
+        AquaTrollLogData {
             attr,
             log_note: None,
             log_data,
-        })
+            log_quality: Some(log_quality),
+            warnings,
+        }
+        .with_read_options(&self.options)
+    }
+
+    /// Read a HTML log file already decoded into a string
+    pub fn read_html_str(&self, content: &str) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        self.read_html(&mut strip_bom(content).as_bytes())
+    }
+
+    /// Read a HTML log file in memory, e.g. downloaded, as of `read_html`
+    pub fn read_html_bytes(
+        &self,
+        mut content: &[u8],
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        self.read_html(&mut content)
     }
 
+    /// Read the first HTML log file in a zip archive
     pub fn read_zipped_html<R: Read + Seek>(
         &self,
         reader: &mut R,
     ) -> Result<AquaTrollLogData, AquaTrollLogError> {
-        let (attr, log_data) = read_zipped_html(reader, &self.datetime_parser)?;
+        let (attr, log_data, log_quality, warnings) = read_zipped_html(reader, &self.options)?;
 
-        Ok(AquaTrollLogData {
+        AquaTrollLogData {
             attr,
             log_note: None,
             log_data,
-        })
+            log_quality: Some(log_quality),
+            warnings,
+        }
+        .with_read_options(&self.options)
+    }
+
+    /// Read the first HTML log file of a zip archive in memory, e.g. downloaded, as of
+    /// `read_zipped_html`
+    pub fn read_zipped_html_bytes(
+        &self,
+        content: &[u8],
+    ) -> Result<AquaTrollLogData, AquaTrollLogError> {
+        self.read_zipped_html(&mut Cursor::new(content))
+    }
+
+    /// Read all HTML log files in a zip archive, in archive order
+    pub fn read_zipped_html_all<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Vec<AquaTrollLogData>, AquaTrollLogError> {
+        read_zipped_html_all(reader, &self.options)?
+            .into_iter()
+            .map(|(attr, log_data, log_quality, warnings)| {
+                AquaTrollLogData {
+                    attr,
+                    log_note: None,
+                    log_data,
+                    log_quality: Some(log_quality),
+                    warnings,
+                }
+                .with_read_options(&self.options)
+            })
+            .collect()
+    }
+
+    /// Read the sensors of a log file of any supported format and the columns they measure,
+    /// without reading the log data
+    ///
+    /// The format is detected from the content. TXT sensors come from the `Sensors` list, HTML
+    /// sensors from the data header, and CSV parameters are given as of a single unknown sensor.
+    pub fn inventory<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Vec<SensorInfo>, AquaTrollLogError> {
+        let start_pos = reader.stream_position()?;
+        let mut head = vec![];
+        reader.by_ref().take(1024).read_to_end(&mut head)?;
+        reader.seek(SeekFrom::Start(start_pos))?;
+
+        match LogFormat::detect("", &head) {
+            Some(LogFormat::Txt) => {
+                read_txt_sensors(&mut decode_txt_reader(reader, self.options.encoding)?)
+            }
+            Some(LogFormat::Csv) => {
+                let mut buf = Vec::new();
+                reader.read_to_end(&mut buf)?;
+                let encoding = self.options.encoding.unwrap_or_else(|| csv_encoding(&buf));
+                read_csv_sensors(decode_buf_reader(Cursor::new(buf), encoding), &self.options)
+            }
+            Some(LogFormat::Html) => read_html_sensors(reader),
+            Some(LogFormat::ZippedHtml) => read_zipped_html_sensors(reader),
+            None => Err(AquaTrollLogError::UnknownFormat),
+        }
+    }
+
+    /// Check a log file of any supported format for problems before ingesting it
+    ///
+    /// Columns of unknown codes of HTML logs, rows failing to parse, a `Record Count` of TXT
+    /// logs not matching the rows and timestamps out of order are reported as issues. Rows of
+    /// TXT logs are checked one by one, CSV and HTML logs stop at the first row failing to
+    /// parse. Errors other than of the content of rows, e.g. of an unknown format, are returned
+    /// as errors.
+    pub fn validate<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Vec<ValidationIssue>, AquaTrollLogError> {
+        let start_pos = reader.stream_position()?;
+        let mut head = vec![];
+        reader.by_ref().take(1024).read_to_end(&mut head)?;
+        reader.seek(SeekFrom::Start(start_pos))?;
+
+        let log = match LogFormat::detect("", &head) {
+            Some(LogFormat::Txt) => {
+                return validate_txt(
+                    &mut decode_txt_reader(reader, self.options.encoding)?,
+                    &self.options,
+                )
+            }
+            Some(LogFormat::Csv) => self.read_csv(reader),
+            Some(LogFormat::Html) => self.read_html(reader),
+            Some(LogFormat::ZippedHtml) => self.read_zipped_html(reader),
+            None => Err(AquaTrollLogError::UnknownFormat),
+        };
+
+        match log {
+            Ok(log) => Ok(table_issues(&log.log_data)),
+            Err(AquaTrollLogError::WithPartialResult(partial)) => {
+                let mut issues: Vec<ValidationIssue> = partial
+                    .errors
+                    .into_iter()
+                    .filter_map(|e| unparseable_row(&e.into(), None))
+                    .collect();
+                issues.extend(table_issues(&partial.result.log_data));
+                Ok(issues)
+            }
+            Err(e) => Ok(vec![unparseable_row(&e, None).ok_or(e)?]),
+        }
+    }
+
+    /// Read all log files bundled in a gzip compressed tar archive
+    ///
+    /// Each entry is dispatched by its extension, or by content when the extension is not
+    /// recognized. Entries of unknown format or failing to be read are skipped, recorded as
+    /// `UnknownFormat` and `UnreadableEntry` warnings of the first log. Archives of no entry
+    /// read fail with the error of the first unreadable entry.
+    #[cfg(all(feature = "flate2", feature = "tar"))]
+    pub fn read_tar_gz<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Vec<AquaTrollLogData>, AquaTrollLogError> {
+        let mut logs: Vec<AquaTrollLogData> = vec![];
+        let mut skipped = vec![];
+        let mut first_error = None;
+
+        for (name, content) in read_tar_gz_entries(reader)? {
+            let mut reader = Cursor::new(content);
+            let log = match LogFormat::detect(&name, reader.get_ref()) {
+                Some(LogFormat::Txt) => self.read_txt(&mut reader),
+                Some(LogFormat::Csv) => self.read_csv(&mut reader),
+                Some(LogFormat::Html) => self.read_html(&mut reader),
+                Some(LogFormat::ZippedHtml) => self.read_zipped_html(&mut reader),
+                None => {
+                    skipped.push(ReadWarning::UnknownFormat(name));
+                    continue;
+                }
+            };
+            match log {
+                Ok(log) => logs.push(log),
+                Err(error) => {
+                    skipped.push(ReadWarning::UnreadableEntry {
+                        name,
+                        error: error.to_string(),
+                    });
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        match (logs.first_mut(), first_error) {
+            (Some(first), _) => first.warnings.extend(skipped),
+            (None, Some(error)) => return Err(error),
+            (None, None) => {}
+        }
+
+        Ok(logs)
     }
 }
 
@@ -160,8 +1587,1763 @@ mod tests {
     fn builder_creates_without_config() {
         let builder = AquaTrollLogReader::default();
         assert_eq!(
-            format!("{:?}", builder.datetime_parser),
+            format!("{:?}", builder.options.datetime_parser),
             format!("{:?}", DateTimeParser::Default)
         );
     }
+
+    #[test]
+    fn residual_bom() {
+        let text = "\u{feff}Report Date: 2025/1/2 PM 12:23:23\r\nReport User Name: USER\r\n";
+        let bytes: Vec<u8> = [0xff, 0xfe]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+
+        let mut reader = decode_reader(&mut Cursor::new(bytes), UTF_16LE).unwrap();
+        let mut attr = Map::new();
+        read_attr(&mut reader, &mut attr, true, &ReadOptions::default()).unwrap();
+        assert_eq!(attr.keys().next().unwrap(), "Report Date");
+        assert_eq!(attr["Report Date"], "2025/1/2 PM 12:23:23");
+    }
+
+    #[test]
+    fn record_count_mismatch() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let bytes = std::fs::read(path).unwrap();
+        let (content, _, _) = UTF_16LE.decode(&bytes);
+        let content = content.replace("Record Count: 5", "Record Count: 6");
+
+        let log = AquaTrollLogReader::default()
+            .read_txt_str(&content)
+            .unwrap();
+        assert_eq!(log.log_data.num_rows(), 5);
+        assert_eq!(
+            log.warnings,
+            vec![ReadWarning::RecordCountMismatch {
+                expected: 6,
+                found: 5
+            }]
+        );
+        assert!(matches!(
+            AquaTrollLogReader::default()
+                .with_strict_record_count(true)
+                .read_txt_str(&content),
+            Err(AquaTrollLogError::RecordCountMismatch {
+                expected: 6,
+                found: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn empty_table() {
+        let csv = "Date/Time,Temp(C)\n";
+        assert!(matches!(
+            AquaTrollLogReader::default().read_csv_str(csv),
+            Err(AquaTrollLogError::EmptyTable)
+        ));
+        let log = AquaTrollLogReader::default()
+            .with_allow_empty(true)
+            .read_csv_str(csv)
+            .unwrap();
+        assert_eq!(log.log_data.columns, vec!["DateTime", "Temp(C)"]);
+        assert_eq!(log.log_data.num_rows(), 0);
+        assert_eq!(log.select(&[]).unwrap().log_data.columns, ["DateTime"]);
+        assert_eq!(log.select_where(|_| false).log_data.columns, ["DateTime"]);
+
+        let html = r#"<table id="isi-report">
+            <tr class="dataHeader" isi-data-table=""><th isi-data-column-header="DateTime">Date Time</th></tr>
+            </table>"#;
+        assert!(matches!(
+            AquaTrollLogReader::default().read_html_str(html),
+            Err(AquaTrollLogError::EmptyTable)
+        ));
+    }
+
+    #[test]
+    fn compact_column_names() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .read_txt(&mut File::open(&path).unwrap())
+            .unwrap();
+        assert_eq!(
+            log.log_data.columns[4],
+            "Oxidation Reduction Potential (ORP) (mV)"
+        );
+
+        let log = AquaTrollLogReader::default()
+            .with_compact_column_names(true)
+            .normalize_temperature_to(Unit::Fahrenheit)
+            .with_column_rename_map(HashMap::from([
+                ("Temperature (C)".to_string(), "temp".to_string()),
+                ("Depth (m)".to_string(), "depth".to_string()),
+            ]))
+            .read_txt(&mut File::open(&path).unwrap())
+            .unwrap();
+        assert_eq!(
+            log.log_data.columns[..5],
+            ["DateTime", "Seconds", "pH (pH)", "pH_mV (mV)", "ORP (mV)"]
+        );
+        assert_eq!(log.log_data.columns[8], "temp");
+        assert_eq!(log.log_data.columns.last().unwrap(), "depth");
+        assert!(log.warnings.is_empty());
+    }
+
+    #[test]
+    fn serial_column_names() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .with_serial_column_names(true)
+            .normalize_temperature_to(Unit::Fahrenheit)
+            .read_txt(&mut File::open(&path).unwrap())
+            .unwrap();
+        let columns = &log.log_data.columns;
+        assert_eq!(columns[..3], ["DateTime", "Seconds", "pH (pH) (999991)"]);
+        // Temperatures of two sensors are told apart
+        assert_eq!(columns[8], "Temperature (°F) (999993)");
+        assert_eq!(columns[16], "Temperature (°F) (999995)");
+        assert!(log.warnings.is_empty());
+
+        let log = AquaTrollLogReader::default()
+            .read_txt(&mut File::open(&path).unwrap())
+            .unwrap();
+        assert_eq!(log.log_data.columns[2], "pH (pH)");
+    }
+
+    #[test]
+    fn downcast_integer_columns() {
+        let csv = "Date/Time,Temp(C),Count\n\
+            2025/1/25 05:15:06 PM,21.6019,3\n\
+            2025/1/25 05:15:36 PM,21.6097,4\n";
+        let log = AquaTrollLogReader::default()
+            .with_downcast_integer_columns(true)
+            .read_csv_str(csv)
+            .unwrap();
+        assert!(matches!(log.log_data.rows[0][1], CellValue::Float64(_)));
+        assert!(matches!(log.log_data.rows[1][2], CellValue::Int64(4)));
+
+        let log = AquaTrollLogReader::default().read_csv_str(csv).unwrap();
+        assert!(matches!(log.log_data.rows[1][2], CellValue::Float64(_)));
+    }
+
+    #[test]
+    fn csv_partial_result() {
+        let csv = "Date/Time,Temp(C),pH(pH)\n\
+            2025/1/25 05:15:06 PM,21.6019,7.40582\n\
+            2025/1/25 05:15:36 PM,21.6097\n\
+            2025/1/25 05:16:06 PM,21.6239,7.40294\n\
+            2025/1/25 05:16:36 PM,21.6365,7.40594,1.0\n";
+        let error = AquaTrollLogReader::default().read_csv_str(csv).unwrap_err();
+        let AquaTrollLogError::WithPartialResult(ref partial) = error else {
+            panic!("Expected an error with partial result");
+        };
+        assert_eq!(partial.line_numbers(), vec![3, 5]);
+        assert_eq!(partial.dropped_rows(), 2);
+        assert_eq!(partial.result.log_data.num_rows(), 2);
+
+        use std::error::Error;
+        let source = error.source().unwrap();
+        assert!(source.downcast_ref::<csv::Error>().is_some());
+        assert_eq!(source.to_string(), partial.errors[0].to_string());
+    }
+
+    #[test]
+    fn skip_note() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .with_skip_note(true)
+            .read_txt(&mut File::open(&path).unwrap())
+            .unwrap();
+        assert!(log.log_note.is_none());
+        assert_eq!(log.log_data.num_rows(), 5);
+        assert!(log.attr.contains_key("Log Configuration"));
+
+        let expected = AquaTrollLogReader::default()
+            .read_txt(&mut File::open(&path).unwrap())
+            .unwrap();
+        assert_eq!(log.log_data.columns, expected.log_data.columns);
+        assert_eq!(log.attr, expected.attr);
+    }
+
+    #[test]
+    fn trailing_summary() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let bytes = std::fs::read(path).unwrap();
+        let (content, _, _) = UTF_16LE.decode(&bytes);
+        let content = format!(
+            "{content}__________________________________________________\r\n\r\nLogged By: USER\r\nStatistics:\r\n    Minimum pH: 7.034\r\n    Maximum pH: 7.036\r\n"
+        );
+
+        let reader = AquaTrollLogReader::default();
+        let log = reader.read_txt_str(&content).unwrap();
+        assert_eq!(log.log_data.num_rows(), 5);
+        assert_eq!(log.attr["Summary"]["Statistics"]["Minimum pH"], "7.034");
+        assert_eq!(log.attr["Summary"]["Logged By"], "USER");
+
+        let bytes: Vec<u8> = content.bytes().collect();
+        let logs = reader.read_txt_multi(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].attr["Summary"], log.attr["Summary"]);
+
+        // Attributes of a second session aren't taken for a summary
+        let content = format!("{content}\r\n{}\r\n{content}", "_".repeat(110));
+        let log = reader.read_txt_str(&content).unwrap();
+        assert_eq!(log.log_data.num_rows(), 5);
+        assert_eq!(log.attr["Summary"]["Logged By"], "USER");
+        assert!(!log.attr["Summary"]
+            .as_object()
+            .unwrap()
+            .contains_key("Log File Properties"));
+
+        let bytes: Vec<u8> = content.bytes().collect();
+        let logs = reader.read_txt_multi(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].attr["Summary"]["Logged By"], "USER");
+        assert_eq!(logs[1].log_note.as_ref().unwrap().num_rows(), 2);
+        assert_eq!(logs[1].attr["Summary"]["Logged By"], "USER");
+    }
+
+    #[test]
+    fn read_warnings() {
+        let log = AquaTrollLogReader::default()
+            .with_csv_options(CsvReadOptions {
+                pad_short_rows: true,
+                ..Default::default()
+            })
+            .with_column_rename_map(HashMap::from([(
+                "Missing".to_string(),
+                "missing".to_string(),
+            )]))
+            .read_csv_str(
+                "Date/Time,Temp(C),Dist(furlong)\n2025/1/25 05:15:06 PM,21.6019,1\n2025/1/25 05:15:36 PM,21.6097\n",
+            )
+            .unwrap();
+
+        assert_eq!(log.log_data.num_rows(), 2);
+        assert_eq!(
+            log.warnings,
+            vec![
+                ReadWarning::ResizedRow {
+                    line_number: Some(3),
+                    expected: 3,
+                    found: 2
+                },
+                ReadWarning::UnknownUnit {
+                    column: "Dist(furlong)".to_string(),
+                    unit: "furlong".to_string()
+                },
+                ReadWarning::RenamedColumnNotFound("Missing".to_string()),
+            ]
+        );
+        assert_eq!(
+            log.warnings[0].to_string(),
+            "Line 3: 2 cells resized to 3 columns"
+        );
+
+        let html = r#"<html><table id="isi-report">
+            <tr class="sectionHeader"><td isi-group="ReportProperties">Report Properties</td></tr>
+            <tr class="sectionMember"><td isi-group-member="ReportProperties">Unlabeled</td></tr>
+            <tr class="dataHeader" isi-data-table="">
+            <th isi-data-column-header="DateTime">Date Time</th>
+            <th isi-data-column-header="Parameter" isi-sensor-serial-number="999991" isi-sensor-type="pH" isi-parameter-type="17" isi-unit-type="145">pH (pH) (999991)</th>
+            </tr>
+            <tr class="data" isi-data-row=""><td class="dateTime">2024-10-09 16:29:46</td><td>6.42</td></tr>
+            </table></html>"#;
+        let log = AquaTrollLogReader::default().read_html_str(html).unwrap();
+        assert!(log.attr["Report Properties"]
+            .as_object()
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            log.warnings,
+            vec![
+                ReadWarning::UnparsedAttribute("Unlabeled".to_string()),
+                ReadWarning::MalformedCode {
+                    attribute: "isi-sensor-type".to_string(),
+                    value: "pH".to_string()
+                },
+                ReadWarning::SensorTypeNotFound("pH".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_str() {
+        let reader = AquaTrollLogReader::default();
+        let path = |name: &str| format!("{}/testing/data/{name}", env!("CARGO_MANIFEST_DIR"));
+
+        let bytes = std::fs::read(path("win_situ_dump.txt")).unwrap();
+        let (content, _, _) = UTF_16LE.decode(&bytes);
+        assert_eq!(
+            reader.read_txt_str(&content).unwrap().to_json().unwrap(),
+            reader
+                .read_txt(&mut Cursor::new(&bytes))
+                .unwrap()
+                .to_json()
+                .unwrap()
+        );
+
+        let bytes = std::fs::read(path("win_situ_record.csv")).unwrap();
+        let (content, _, _) = ISO_8859_3.decode(&bytes);
+        assert_eq!(
+            reader
+                .read_csv_str(&format!("\u{feff}{content}"))
+                .unwrap()
+                .to_json()
+                .unwrap(),
+            reader
+                .read_csv(&mut Cursor::new(&bytes))
+                .unwrap()
+                .to_json()
+                .unwrap()
+        );
+
+        let log = reader
+            .read_html_str(
+                r#"<table id="isi-report">
+                <tr isi-data-table=""><td isi-data-column-header="DateTime">Date Time</td><td isi-data-column-header="Parameter" isi-parameter-type="17" isi-unit-type="145">pH (pH)</td></tr>
+                <tr isi-data-row=""><td>2024-10-09 16:29:46</td><td>6.4217362</td></tr>
+                </table>"#,
+            )
+            .unwrap();
+        assert_eq!(log.log_data.columns, vec!["DateTime", "pH (pH)"]);
+        assert_eq!(log.row_count(), 1);
+    }
+
+    #[test]
+    fn utf8_txt() {
+        let attr_block = "Report Date: 2025/1/2 PM 12:23:23\r\nReport User Name: USER\r\n";
+        assert_eq!(txt_encoding(b"\xef\xbb\xbfReport Date"), UTF_8);
+        assert_eq!(txt_encoding(attr_block.as_bytes()), UTF_8);
+        let utf16: Vec<u8> = attr_block
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        assert_eq!(txt_encoding(&utf16), UTF_16LE);
+        assert_eq!(txt_encoding(&[0xff, 0xfe, 0x52, 0x00]), UTF_16LE);
+
+        for bom in ["\u{feff}", ""] {
+            let text = format!("{bom}{attr_block}");
+            let mut reader = decode_txt_reader(&mut Cursor::new(text.as_bytes()), None).unwrap();
+            let mut attr = Map::new();
+            read_attr(&mut reader, &mut attr, true, &ReadOptions::default()).unwrap();
+            assert_eq!(attr["Report Date"], "2025/1/2 PM 12:23:23");
+            assert_eq!(attr["Report User Name"], "USER");
+        }
+
+        let bytes = std::fs::read(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let (text, _) = UTF_16LE.decode_with_bom_removal(&bytes);
+        let reader = AquaTrollLogReader::default();
+        let log = reader.read_txt(&mut Cursor::new(text.as_bytes())).unwrap();
+        assert_eq!(
+            log.to_json().unwrap(),
+            reader
+                .read_txt(&mut Cursor::new(&bytes))
+                .unwrap()
+                .to_json()
+                .unwrap()
+        );
+        let rows = reader.stream_txt_rows(text.as_bytes()).unwrap();
+        assert_eq!(rows.count(), log.row_count());
+    }
+
+    #[test]
+    fn windows_1252_txt() {
+        let attr_block = "Other Log Settings\r\n    Temperature: 21.3015 (°C)\r\n";
+        let (windows_1252, _, _) = WINDOWS_1252.encode(attr_block);
+        assert_eq!(txt_encoding(&windows_1252), WINDOWS_1252);
+        assert_eq!(txt_encoding(&attr_block.as_bytes()[..37]), UTF_8);
+
+        let mut reader = decode_txt_reader(&mut Cursor::new(windows_1252), None).unwrap();
+        let mut attr = Map::new();
+        read_attr(&mut reader, &mut attr, true, &ReadOptions::default()).unwrap();
+        assert_eq!(attr["Other Log Settings"]["Temperature"], "21.3015 (°C)");
+
+        let bytes = std::fs::read(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let (text, _) = UTF_16LE.decode_with_bom_removal(&bytes);
+        // Chinese time zone names are not of Windows-1252
+        let text = text.replace("台北標準時間", "UTC");
+        let (windows_1252, _, _) = WINDOWS_1252.encode(&text);
+        let reader = AquaTrollLogReader::default();
+        let log = reader.read_txt(&mut Cursor::new(&windows_1252)).unwrap();
+        let conductivity = "Actual Conductivity (µS/cm)".to_string();
+        assert!(log.log_data.columns.contains(&conductivity));
+
+        // Streamed rows are of the encoding detected from the head spanning the column headers,
+        // the other readers detect it from the whole file as well
+        let rows = reader.stream_txt_rows(Cursor::new(&windows_1252)).unwrap();
+        assert!(rows.columns().contains(&conductivity));
+        assert_eq!(rows.count(), 5);
+        let layout = reader
+            .inspect_txt_layout(Cursor::new(&windows_1252))
+            .unwrap();
+        assert!(layout.iter().any(|column| column.name == conductivity));
+        assert_eq!(
+            reader
+                .inventory(&mut Cursor::new(&windows_1252))
+                .unwrap()
+                .len(),
+            6
+        );
+        assert_eq!(
+            reader.validate(&mut Cursor::new(&windows_1252)).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn csv_encodings() {
+        let content = "Date/Time,CNDCT(µS/cm)\n2025/1/25 05:15:00 PM,271.5\n";
+        let reader = AquaTrollLogReader::default();
+        let column = |log: AquaTrollLogData| log.log_data.columns[1].clone();
+
+        let log = reader
+            .read_csv(&mut Cursor::new(content.as_bytes()))
+            .unwrap();
+        assert_eq!(column(log), "CNDCT(µS/cm)");
+        let with_bom = format!("\u{feff}{content}");
+        let log = reader
+            .read_csv(&mut Cursor::new(with_bom.as_bytes()))
+            .unwrap();
+        assert_eq!(column(log), "CNDCT(µS/cm)");
+
+        let (windows_1252, _, _) = encoding_rs::WINDOWS_1252.encode(content);
+        let log = reader
+            .read_csv(&mut Cursor::new(windows_1252.to_vec()))
+            .unwrap();
+        assert_eq!(column(log), "CNDCT(µS/cm)");
+        let log = reader
+            .read_csv_with_encoding(
+                &mut Cursor::new(windows_1252.to_vec()),
+                Encoding::for_label(b"windows-1252").unwrap(),
+            )
+            .unwrap();
+        assert_eq!(column(log), "CNDCT(µS/cm)");
+        let log = AquaTrollLogReader::default()
+            .with_encoding(encoding_rs::WINDOWS_1252)
+            .read_csv(&mut Cursor::new(content.as_bytes()))
+            .unwrap();
+        assert_eq!(column(log), "CNDCT(ÂµS/cm)");
+
+        assert_eq!(csv_encoding(content.as_bytes()), UTF_8);
+        assert_eq!(csv_encoding(&content.as_bytes()[..17]), UTF_8);
+        assert_eq!(csv_encoding(&windows_1252), ISO_8859_3);
+
+        // Characters out of ASCII of late rows
+        let mut content = "Date/Time,Temp(C),Note\n".to_string();
+        for _ in 0..100 {
+            content.push_str("2025/1/25 05:15:00 PM,21.5,\n");
+        }
+        content.push_str("2025/1/25 05:15:30 PM,21.6,Above 21.5 °C\n");
+        let (windows_1252, _, _) = encoding_rs::WINDOWS_1252.encode(&content);
+        assert!(windows_1252.len() > 1024);
+        let log = reader
+            .read_csv(&mut Cursor::new(windows_1252.to_vec()))
+            .unwrap();
+        assert!(matches!(
+            &log.log_data.rows[100][2],
+            CellValue::Text(s) if s == "Above 21.5 °C"
+        ));
+    }
+
+    #[test]
+    fn detect_log_format() {
+        assert_eq!(LogFormat::detect("a.TXT", b""), Some(LogFormat::Txt));
+        assert_eq!(
+            LogFormat::detect("a", &[0xff, 0xfe, 0x52]),
+            Some(LogFormat::Txt)
+        );
+        assert_eq!(
+            LogFormat::detect("a", b"Report Date: 2025/1/26 AM 08:47:06\r\n"),
+            Some(LogFormat::Txt)
+        );
+        assert_eq!(
+            LogFormat::detect("a", b"<!DOCTYPE html><html>"),
+            Some(LogFormat::Html)
+        );
+        assert_eq!(
+            LogFormat::detect("a", b"Date/Time,Temp(C)\n"),
+            Some(LogFormat::Csv)
+        );
+        assert_eq!(LogFormat::detect("a", b"unknown"), None);
+    }
+
+    #[test]
+    fn txt_time_zone() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+
+        let log = AquaTrollLogReader::default()
+            .read_txt(&mut std::fs::File::open(&path).unwrap())
+            .unwrap();
+        let taipei = FixedOffset::east_opt(8 * 3600);
+        assert_eq!(log.log_data.timezone, taipei);
+        assert_eq!(log.log_note.unwrap().timezone, taipei);
+        assert_eq!(
+            log.log_data
+                .serialize(serde_json::value::Serializer)
+                .unwrap()[0]["DateTime"],
+            "2025-01-25T16:24:59+08:00"
+        );
+
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let log = AquaTrollLogReader::default()
+            .with_timezone(utc)
+            .read_txt(&mut std::fs::File::open(&path).unwrap())
+            .unwrap();
+        assert_eq!(log.log_data.timezone, Some(utc));
+
+        // Timestamps with a time zone abbreviation, in a wider column
+        let bytes = std::fs::read(&path).unwrap();
+        let (content, _, _) = UTF_16LE.decode(&bytes);
+        let content = content
+            .replace("----------------------     ", "-------------------------- ")
+            .lines()
+            .map(|line| match line.starts_with("2025/1/25 PM") {
+                true => format!("{} JST{}", &line[..21], &line[25..]),
+                false => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        let log = AquaTrollLogReader::default()
+            .read_txt_str(&content)
+            .unwrap();
+        let tokyo = FixedOffset::east_opt(9 * 3600);
+        assert_eq!(log.log_data.timezone, tokyo);
+        assert_eq!(log.log_note.unwrap().timezone, tokyo);
+        assert_eq!(
+            log.log_data
+                .serialize(serde_json::value::Serializer)
+                .unwrap()[0]["DateTime"],
+            "2025-01-25T16:24:59+09:00"
+        );
+    }
+
+    #[test]
+    fn export_bundle() {
+        let mut file = std::fs::File::open(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let log = AquaTrollLogReader::default().read_txt(&mut file).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("aqua_troll_export_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        log.export_bundle(&dir, "log").unwrap();
+
+        let attr: Map<String, Value> =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("log_attr.json")).unwrap())
+                .unwrap();
+        assert_eq!(attr, log.attr);
+
+        let note = std::fs::read_to_string(dir.join("log_note.csv")).unwrap();
+        assert_eq!(note.lines().next(), Some("DateTime,Note"));
+        assert_eq!(note.lines().count(), 3);
+
+        let data = std::fs::read_to_string(dir.join("log_data.csv")).unwrap();
+        let mut lines = data.lines();
+        assert!(lines.next().unwrap().starts_with("DateTime,Seconds,"));
+        assert!(lines.next().unwrap().starts_with("2025-01-25 16:24:59,0,"));
+        assert_eq!(lines.count(), 4);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stream_txt_rows() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let reader = AquaTrollLogReader::default();
+        let log = reader
+            .read_txt(&mut std::fs::File::open(&path).unwrap())
+            .unwrap();
+
+        let rows = reader
+            .stream_txt_rows(std::fs::File::open(&path).unwrap())
+            .unwrap();
+        assert_eq!(rows.columns(), log.log_data.columns.as_slice());
+        assert_eq!(rows.attr()["Record Count"], 5);
+        assert_eq!(rows.timezone(), log.log_data.timezone);
+
+        let rows: Vec<Row> = rows.collect::<Result<_, _>>().unwrap();
+        assert_eq!(rows.len(), log.log_data.num_rows());
+        for (row, expected) in rows.iter().zip(&log.log_data.rows) {
+            assert_eq!(row.values.len(), expected.len());
+            assert_eq!(
+                row.values
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                expected.iter().map(ToString::to_string).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(rows[0].datetime, log.log_data.timestamps().first().copied());
+    }
+
+    #[test]
+    fn interleaved_notes() {
+        let csv = "Date/Time,Record Type,Note,Temp(C)\n\
+                   2025/1/26 05:15:00 PM,Note,Log Started,\n\
+                   2025/1/26 05:15:06 PM,Data,,21.6019\n\
+                   2025/1/26 05:15:30 PM,Note,Sensor Cleaned,\n\
+                   2025/1/26 05:15:36 PM,Data,,21.6097\n";
+        let log = AquaTrollLogReader::default()
+            .with_note_discriminator("Record Type", "Note")
+            .read_csv(&mut Cursor::new(csv.as_bytes()))
+            .unwrap();
+
+        let log_note = log.log_note.unwrap();
+        assert_eq!(log_note.columns, vec![DATETIME_COLUMN, "Note"]);
+        assert_eq!(
+            log_note
+                .column_values(1)
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["Log Started", "Sensor Cleaned"]
+        );
+        assert_eq!(log.log_data.columns, vec![DATETIME_COLUMN, "Temp(C)"]);
+        assert_eq!(log.log_data.num_rows(), 2);
+        assert!(matches!(log.log_data.rows[1][1], CellValue::Float64(v) if v == 21.6097));
+
+        assert!(AquaTrollLogReader::default()
+            .read_csv(&mut Cursor::new(csv.as_bytes()))
+            .is_err());
+
+        // Interleaved notes not matching the columns of the log note are left in the log data
+        let table = |columns: &[&str], rows: &[&[&str]]| {
+            rows.iter()
+                .fold(
+                    TableBuilder::new()
+                        .field_names(columns.iter().map(ToString::to_string).collect())
+                        .field_types(&HashMap::from([
+                            ("Record Type".to_string(), ColumnType::Text),
+                            ("Remark".to_string(), ColumnType::Text),
+                        ])),
+                    |builder, row| {
+                        builder
+                            .try_push_row(row.iter().map(ToString::to_string).collect())
+                            .unwrap()
+                    },
+                )
+                .try_build()
+                .unwrap()
+        };
+        let log = AquaTrollLogData {
+            attr: Map::new(),
+            log_note: Some(table(
+                &["Date and Time", "Remark"],
+                &[&["2025/1/26 05:14:00 PM", "Deployed"]],
+            )),
+            log_data: table(
+                &["Date and Time", "Record Type", "Note", "Temp(C)"],
+                &[
+                    &["2025/1/26 05:15:00 PM", "Note", "Log Started", ""],
+                    &["2025/1/26 05:15:06 PM", "Data", "", "21.6019"],
+                ],
+            ),
+            log_quality: None,
+            warnings: vec![],
+        }
+        .split_interleaved_notes(&ReadOptions {
+            note_discriminator: Some(("Record Type".to_string(), "Note".to_string())),
+            ..Default::default()
+        });
+        assert_eq!(log.log_data.columns.len(), 4);
+        assert_eq!(log.log_data.num_rows(), 2);
+        assert_eq!(log.log_note.unwrap().num_rows(), 1);
+        assert_eq!(
+            log.warnings,
+            vec![ReadWarning::NoteColumnsMismatch(vec![
+                DATETIME_COLUMN.to_string(),
+                "Note".to_string()
+            ])]
+        );
+    }
+
+    #[test]
+    fn txt_multi_sessions() {
+        let bytes = std::fs::read(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let (text, _) = UTF_16LE.decode_with_bom_removal(&bytes);
+        let section_break = format!("\r\n{}\r\n", "_".repeat(110));
+        let log_data_section = &text[text.find("Log Data:").unwrap()..];
+        let dump = [
+            &*text,
+            &section_break,
+            &text,
+            &section_break,
+            log_data_section,
+        ]
+        .concat();
+        let bytes: Vec<u8> = [0xff, 0xfe]
+            .into_iter()
+            .chain(dump.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+
+        let reader = AquaTrollLogReader::default();
+        let logs = reader.read_txt_multi(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(logs.len(), 3);
+        for log in &logs {
+            assert_eq!(log.log_data.num_rows(), 5);
+            assert_eq!(log.attr["Log Data"]["Record Count"], 5);
+            assert_eq!(log.attr["Report Date"], "2025/1/26 AM 08:47:06");
+            assert_eq!(log.log_data.timezone, FixedOffset::east_opt(8 * 3600));
+        }
+        assert_eq!(logs[0].log_note.as_ref().unwrap().num_rows(), 2);
+        assert_eq!(logs[1].log_note.as_ref().unwrap().num_rows(), 2);
+        assert!(logs[2].log_note.is_none());
+
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let logs = reader
+            .read_txt_multi(&mut File::open(path).unwrap())
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+    }
+
+    #[test]
+    fn derive_specific_conductivity() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .read_txt(&mut File::open(path).unwrap())
+            .unwrap();
+
+        let model = ConductivityModel::from_attr(&log.attr).unwrap();
+        assert_eq!(model, ConductivityModel::StandardMethods);
+        let derived = log.derive_specific_conductivity(model, 25.0).unwrap();
+        let index = log
+            .log_data
+            .columns
+            .iter()
+            .position(|c| c == "Specific Conductivity (µS/cm)")
+            .unwrap();
+        assert_eq!(derived.len(), log.log_data.num_rows());
+        for (derived, logged) in derived.iter().zip(log.log_data.column_values(index)) {
+            let (Some(derived), CellValue::Float64(logged)) = (derived, logged) else {
+                panic!("Expected conductivity readings");
+            };
+            assert!((derived - logged).abs() / logged < 1e-3);
+        }
+
+        let csv_path = format!(
+            "{}/testing/data/win_situ_record.csv",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .read_csv(&mut File::open(csv_path).unwrap())
+            .unwrap();
+        assert!(matches!(
+            log.derive_specific_conductivity(model, 25.0),
+            Err(AquaTrollLogError::ParameterNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn mask_out_of_water() {
+        let data_dir = format!("{}/testing/data", env!("CARGO_MANIFEST_DIR"));
+        let reader = AquaTrollLogReader::default();
+        let depth = |log: &AquaTrollLogData| {
+            log.log_data
+                .column_descriptors()
+                .iter()
+                .position(|d| d.parameter == Some(Parameter::Depth))
+                .unwrap()
+        };
+
+        // Readings of both logs are taken in water
+        let txt = reader
+            .read_txt(&mut File::open(format!("{data_dir}/win_situ_dump.txt")).unwrap())
+            .unwrap();
+        let masked = txt.mask_out_of_water(PressureReference::Vented).unwrap();
+        let i = depth(&txt);
+        assert!(masked
+            .log_data
+            .rows
+            .iter()
+            .all(|row| matches!(row[i], CellValue::Float64(v) if v >= 0.0)));
+
+        let html = reader
+            .read_zipped_html(
+                &mut File::open(format!(
+                    "{data_dir}/VuSitu_LiveReadings_2025-01-25_20-29-44_Device_Location.zip"
+                ))
+                .unwrap(),
+            )
+            .unwrap();
+        let masked = html.mask_out_of_water(PressureReference::Vented).unwrap();
+        let i = depth(&html);
+        assert_eq!(masked.row_count(), 1053);
+        assert!(masked
+            .log_data
+            .rows
+            .iter()
+            .all(|row| matches!(row[i], CellValue::Float64(_))));
+
+        // Rows on both sides of the waterline of an absolute pressure sensor
+        let csv = "Date/Time,Pressure (PSI),Barometric Pressure (PSI),Depth (m)\n\
+            2025/1/25 05:15:06 PM,16.2,14.7,1.05\n\
+            2025/1/25 05:15:36 PM,14.7,14.7,0.01\n\
+            2025/1/25 05:16:06 PM,15.1,14.7,0.28\n";
+        let log = reader.read_csv_str(csv).unwrap();
+        let masked = log.mask_out_of_water(PressureReference::Absolute).unwrap();
+        let i = depth(&log);
+        assert!(matches!(masked.log_data.rows[0][i], CellValue::Float64(v) if v == 1.05));
+        assert!(matches!(masked.log_data.rows[1][i], CellValue::Null));
+        assert!(matches!(masked.log_data.rows[2][i], CellValue::Float64(v) if v == 0.28));
+        let masked = log.mask_out_of_water(PressureReference::Vented).unwrap();
+        assert!(matches!(masked.log_data.rows[1][i], CellValue::Float64(v) if v == 0.01));
+    }
+
+    #[test]
+    fn size_accessors() {
+        let data_dir = format!("{}/testing/data", env!("CARGO_MANIFEST_DIR"));
+        let reader = AquaTrollLogReader::default();
+
+        let txt = reader
+            .read_txt(&mut File::open(format!("{data_dir}/win_situ_dump.txt")).unwrap())
+            .unwrap();
+        assert_eq!(txt.row_count(), 5);
+        assert_eq!(txt.column_count(), txt.log_data.columns.len());
+        assert!(!txt.is_empty());
+        assert_eq!(txt.note_count(), 2);
+
+        let csv = reader
+            .read_csv(&mut File::open(format!("{data_dir}/win_situ_record.csv")).unwrap())
+            .unwrap();
+        assert_eq!(csv.column_count(), 11);
+        assert_eq!(csv.note_count(), 0);
+    }
+
+    #[test]
+    fn inspect_txt_layout() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let reader = AquaTrollLogReader::default();
+        let layout = reader
+            .inspect_txt_layout(File::open(&path).unwrap())
+            .unwrap();
+        let log = reader.read_txt(&mut File::open(&path).unwrap()).unwrap();
+
+        assert_eq!(layout.len(), log.log_data.num_columns());
+        assert_eq!(layout[0].name, "Date and Time");
+        assert_eq!((layout[0].start, layout[0].end), (0, Some(22)));
+        assert_eq!(layout[1].name, "Seconds");
+        assert!(layout.last().unwrap().end.is_none());
+        assert!(layout.windows(2).all(|w| w[0].end.unwrap() < w[1].start));
+    }
+
+    #[test]
+    fn normalize_temperature() {
+        let csv = "Date/Time,Temperature (F),Temp(C),Depth (ft)\n\
+                   2025/1/26 05:15:06 PM,212,25,3.5\n";
+        let log = AquaTrollLogReader::default()
+            .normalize_temperature_to(Unit::Celsius)
+            .with_column_rename_map(HashMap::from([(
+                "Temperature (F)".to_string(),
+                "water_temp".to_string(),
+            )]))
+            .read_csv(&mut Cursor::new(csv.as_bytes()))
+            .unwrap();
+
+        assert_eq!(
+            log.log_data.columns,
+            vec![DATETIME_COLUMN, "water_temp", "Temp(C)", "Depth (ft)"]
+        );
+        assert!(
+            matches!(log.log_data.rows[0][1], CellValue::Float64(v) if (v - 100.0).abs() < 1e-9)
+        );
+        assert!(matches!(log.log_data.rows[0][2], CellValue::Float64(v) if v == 25.0));
+        assert!(matches!(log.log_data.rows[0][3], CellValue::Float64(v) if v == 3.5));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .read_txt(&mut File::open(path).unwrap())
+            .unwrap();
+
+        let json = log.to_json().unwrap();
+        assert_eq!(
+            json["log_data"][0][DATETIME_COLUMN],
+            "2025-01-25T16:24:59+08:00"
+        );
+        let parsed = AquaTrollLogData::from_json(&json).unwrap();
+        assert_eq!(parsed.to_json().unwrap(), json);
+        assert_eq!(parsed.log_data.num_rows(), log.log_data.num_rows());
+        assert_eq!(parsed.log_data.timezone, log.log_data.timezone);
+        assert_eq!(parsed.note_count(), 2);
+
+        let text = serde_json::to_string(&parsed).unwrap();
+        let parsed = AquaTrollLogData::from_json(&serde_json::from_str(&text).unwrap()).unwrap();
+        assert_eq!(parsed.to_json().unwrap(), json);
+
+        assert!(matches!(
+            AquaTrollLogData::from_json(&serde_json::json!({"log_data": [1]})),
+            Err(AquaTrollLogError::InvalidData)
+        ));
+    }
+
+    #[test]
+    fn read_bytes() {
+        let data_dir = format!("{}/testing/data", env!("CARGO_MANIFEST_DIR"));
+        let read = |name: &str| std::fs::read(format!("{data_dir}/{name}")).unwrap();
+        let reader = AquaTrollLogReader::default();
+
+        let log = reader.read_txt_bytes(&read("win_situ_dump.txt")).unwrap();
+        assert_eq!(log.row_count(), 5);
+        assert_eq!(log.note_count(), 2);
+
+        let content = read("win_situ_record.csv");
+        let log = reader.read_csv_bytes(&content).unwrap();
+        let expected = reader.read_csv(&mut Cursor::new(&content)).unwrap();
+        assert_eq!(log.to_json().unwrap(), expected.to_json().unwrap());
+
+        let content = read("VuSitu_LiveReadings_2025-01-25_20-29-44_Device_Location.zip");
+        let log = reader.read_zipped_html_bytes(&content).unwrap();
+        let expected = reader.read_zipped_html(&mut Cursor::new(&content)).unwrap();
+        assert_eq!(log.to_json().unwrap(), expected.to_json().unwrap());
+
+        let mut html = vec![];
+        zip::ZipArchive::new(Cursor::new(&content))
+            .unwrap()
+            .by_index(0)
+            .unwrap()
+            .read_to_end(&mut html)
+            .unwrap();
+        let log = reader.read_html_bytes(&html).unwrap();
+        assert_eq!(log.to_json().unwrap(), expected.to_json().unwrap());
+    }
+
+    #[test]
+    fn json_pretty() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .read_txt(&mut File::open(path).unwrap())
+            .unwrap();
+
+        let pretty = log.to_json_pretty().unwrap();
+        assert!(pretty.starts_with("{\n  \"attr\": {"));
+        let json: Value = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(json, log.to_json().unwrap());
+
+        let mut buf = vec![];
+        log.write_json_pretty(&mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), pretty);
+    }
+
+    #[test]
+    fn json_rows() {
+        let path = format!(
+            "{}/testing/data/win_situ_record.csv",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .read_csv(&mut File::open(path).unwrap())
+            .unwrap();
+
+        let first = log.rows().next().unwrap();
+        assert_eq!(first.len(), log.log_data.num_columns());
+        assert_eq!(
+            first,
+            vec![
+                Value::from("2025-01-26T17:15:06"),
+                Value::from(21.6019),
+                Value::from(416.245),
+                Value::from(445.136),
+                Value::from(2402.43),
+                Value::from(0.216156),
+                Value::from(289.339),
+                Value::from(7.40582),
+                Value::from(173.966),
+                Value::from(5.43175),
+                Value::from(56.0774),
+            ]
+        );
+        assert_eq!(log.rows().count(), log.log_data.num_rows());
+    }
+
+    #[test]
+    fn row_at_extreme() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .read_txt(&mut File::open(path).unwrap())
+            .unwrap();
+
+        // The first of ties
+        let row = log.row_at_extreme(Parameter::PH, Extreme::Min).unwrap();
+        assert_eq!(row["DateTime"], "2025-01-25T16:24:59+08:00");
+        assert_eq!(row["pH (pH)"], 7.034);
+        let row = log.row_at_extreme(Parameter::PH, Extreme::Max).unwrap();
+        assert_eq!(row["DateTime"], "2025-01-25T16:25:59+08:00");
+        assert_eq!(row["pH(mV) (mV)"], -15.29);
+        assert!(matches!(
+            log.row_at_extreme(Parameter::Chloride, Extreme::Max),
+            Err(AquaTrollLogError::ParameterNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn merge_logs() {
+        let reader = AquaTrollLogReader::default();
+        let logs = [
+            "Date/Time,Temp(C)\n2025/1/25 05:15:06 PM,21.6019\n",
+            "Date/Time,Temp(C)\n2025/1/26 05:15:06 PM,21.6097\n",
+        ]
+        .map(|content| reader.read_csv_str(content).unwrap());
+
+        let log = AquaTrollLogData::merge(&logs).unwrap();
+        assert_eq!(log.log_data.num_rows(), 2);
+        assert_eq!(log.attr["Merged Logs"], 2);
+        assert!(log.warnings().is_empty());
+
+        let zoned = AquaTrollLogReader::default()
+            .with_timezone(FixedOffset::east_opt(8 * 3600).unwrap())
+            .read_csv_str("Date/Time,Temp(C)\n2025/1/27 05:15:06 PM,21.6101\n")
+            .unwrap();
+        let unzoned = reader
+            .read_csv_str("Date/Time,Temp(C)\n2025/1/28 05:15:06 PM,21.6102\n")
+            .unwrap();
+        let log = AquaTrollLogData::merge(&[zoned, unzoned]).unwrap();
+        assert_eq!(log.warnings(), [ReadWarning::MixedTimeZones]);
+
+        let other = reader
+            .read_csv_str("Date/Time,pH(pH)\n2025/1/27 05:15:06 PM,7.4\n")
+            .unwrap();
+        let [first, _] = logs;
+        assert!(matches!(
+            AquaTrollLogData::merge(&[first, other]),
+            Err(AquaTrollLogError::SchemaMismatch(column)) if column == "Temp(C)"
+        ));
+    }
+
+    #[test]
+    fn merge_html_logs() {
+        let html = |datetime: &str, quality: u8| {
+            format!(
+                r#"<html><table id="isi-report">
+                <tr class="dataHeader" isi-data-table="">
+                <th isi-data-column-header="DateTime">Date Time</th>
+                <th isi-data-column-header="Parameter" isi-sensor-serial-number="999991" isi-sensor-type="58" isi-parameter-type="17" isi-unit-type="145">pH (pH) (999991)</th>
+                </tr>
+                <tr class="data" isi-data-row=""><td class="dateTime">{datetime}</td><td isi-data-quality="{quality}">6.42</td></tr>
+                </table></html>"#
+            )
+        };
+        let reader = AquaTrollLogReader::default();
+        let logs = [
+            reader
+                .read_html_str(&html("2024-10-09 16:29:46", 4))
+                .unwrap(),
+            reader
+                .read_html_str(&html("2024-10-10 16:29:46", 5))
+                .unwrap(),
+        ];
+
+        let log = AquaTrollLogData::merge(&logs).unwrap();
+        assert_eq!(log.log_data.num_rows(), 2);
+        let log_quality = log.log_quality.unwrap();
+        assert_eq!(log_quality.columns, log.log_data.columns);
+        assert_eq!(
+            log_quality.column_values(1).collect::<Vec<_>>(),
+            [Some(DataQuality(4)), Some(DataQuality(5))]
+        );
+    }
+
+    #[test]
+    fn ndjson() {
+        let log = AquaTrollLogReader::default()
+            .read_csv_str("Date/Time,Temp(C),pH(pH)\n2025/1/25 05:15:06 PM,21.6019,\n2025/1/25 05:15:36 PM,21.6097,7.4\n")
+            .unwrap();
+
+        let mut buf = vec![];
+        log.to_ndjson(&mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            concat!(
+                r#"{"DateTime":"2025-01-25T17:15:06","Temp(C)":21.6019,"pH(pH)":null}"#,
+                "\n",
+                r#"{"DateTime":"2025-01-25T17:15:36","Temp(C)":21.6097,"pH(pH)":7.4}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn column_rename_map() {
+        let path = format!(
+            "{}/testing/data/win_situ_record.csv",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let log = AquaTrollLogReader::default()
+            .with_column_rename_map(HashMap::from([
+                ("Temp(C)".to_string(), "temperature_c".to_string()),
+                ("pH(pH)".to_string(), "ph".to_string()),
+                ("Missing".to_string(), "missing".to_string()),
+            ]))
+            .read_csv(&mut File::open(path).unwrap())
+            .unwrap();
+
+        assert_eq!(log.log_data.columns[1], "temperature_c");
+        assert_eq!(log.log_data.columns[7], "ph");
+        assert!(!log.log_data.columns.iter().any(|c| c == "missing"));
+    }
+
+    #[test]
+    fn canonical_datetime_column() {
+        let data_dir = format!("{}/testing/data", env!("CARGO_MANIFEST_DIR"));
+        let reader = AquaTrollLogReader::default();
+
+        let txt = reader
+            .read_txt(&mut File::open(format!("{data_dir}/win_situ_dump.txt")).unwrap())
+            .unwrap();
+        assert_eq!(txt.log_data.column_name(0), DATETIME_COLUMN);
+        assert_eq!(txt.log_note.unwrap().column_name(0), DATETIME_COLUMN);
+
+        let csv = reader
+            .read_csv(&mut File::open(format!("{data_dir}/win_situ_record.csv")).unwrap())
+            .unwrap();
+        assert_eq!(csv.log_data.column_name(0), DATETIME_COLUMN);
+
+        let html = reader
+            .read_zipped_html(
+                &mut File::open(format!(
+                    "{data_dir}/VuSitu_LiveReadings_2025-01-25_20-29-44_Device_Location.zip"
+                ))
+                .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(html.log_data.column_name(0), DATETIME_COLUMN);
+
+        for header in ["Date and Time", "Date Time", "Date/Time", "DateTime"] {
+            let mut reader =
+                std::io::Cursor::new(format!("{header},Temp(C)\n2025-1-25 17:15:00,21.6\n"));
+            let log = AquaTrollLogReader::default().read_csv(&mut reader).unwrap();
+            assert_eq!(log.log_data.columns, vec![DATETIME_COLUMN, "Temp(C)"]);
+            assert_eq!(log.log_data.timestamps().len(), 1);
+        }
+    }
+
+    #[test]
+    fn timestamp_column_name() {
+        let data_dir = format!("{}/testing/data", env!("CARGO_MANIFEST_DIR"));
+        let reader = AquaTrollLogReader::default().with_timestamp_column_name("timestamp");
+
+        let txt = reader
+            .read_txt(&mut File::open(format!("{data_dir}/win_situ_dump.txt")).unwrap())
+            .unwrap();
+        assert_eq!(txt.log_data.column_name(0), "timestamp");
+        assert_eq!(txt.log_note.unwrap().column_name(0), "timestamp");
+
+        let csv = reader
+            .read_csv_str("Date/Time,Temp(C)\n2025/1/25 05:15:06 PM,21.6019\n")
+            .unwrap();
+        assert_eq!(csv.log_data.columns, vec!["timestamp", "Temp(C)"]);
+        assert_eq!(csv.log_data.timestamps().len(), 1);
+    }
+
+    #[test]
+    fn inventory() {
+        let data_dir = format!("{}/testing/data", env!("CARGO_MANIFEST_DIR"));
+        let reader = AquaTrollLogReader::default();
+
+        let sensors = reader
+            .inventory(&mut File::open(format!("{data_dir}/win_situ_dump.txt")).unwrap())
+            .unwrap();
+        assert_eq!(sensors.len(), 6);
+        assert_eq!(sensors[0].serial, Some(999991));
+        assert_eq!(sensors[0].model.as_deref(), Some("pH/ORP"));
+        assert_eq!(sensors[0].parameters[0], "pH (pH)");
+
+        let sensors = reader
+            .inventory(
+                &mut File::open(format!(
+                    "{data_dir}/VuSitu_LiveReadings_2025-01-25_20-29-44_Device_Location.zip"
+                ))
+                .unwrap(),
+            )
+            .unwrap();
+        assert!(!sensors.is_empty());
+        assert!(sensors
+            .iter()
+            .all(|s| s.serial.is_some() && !s.parameters.is_empty()));
+
+        let sensors = reader
+            .inventory(&mut File::open(format!("{data_dir}/win_situ_record.csv")).unwrap())
+            .unwrap();
+        assert_eq!(sensors.len(), 1);
+        assert!(sensors[0].parameters.contains(&"CNDCT(µS/cm)".to_string()));
+
+        let result = reader.inventory(&mut Cursor::new(b"unknown".to_vec()));
+        assert!(matches!(result, Err(AquaTrollLogError::UnknownFormat)));
+    }
+
+    #[test]
+    fn column_descriptors() {
+        let mut file = File::open(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let log = AquaTrollLogReader::default().read_txt(&mut file).unwrap();
+
+        let columns = log.columns();
+        assert_eq!(columns.len(), log.log_data.num_columns());
+        assert_eq!(columns[0].name, "DateTime");
+        assert!(columns[0].sensor_serial.is_none());
+        let ph = &columns[2];
+        assert_eq!(ph.name, "pH (pH)");
+        assert!(matches!(ph.parameter, Some(Parameter::PH)));
+        assert!(matches!(ph.unit, Some(Unit::PH)));
+        assert_eq!(ph.sensor_serial, Some(999991));
+    }
+
+    #[test]
+    fn time_gaps() {
+        let mut reader = std::io::Cursor::new(
+            "Date/Time,Temp(C)
+2025/1/25 05:15:00 PM,21.6
+2025/1/25 05:15:30 PM,21.6
+2025/1/25 05:16:00 PM,21.6
+2025/1/25 05:20:00 PM,21.7
+2025/1/25 05:20:31 PM,21.7
+2025/1/25 05:21:00 PM,21.7
+",
+        );
+        let log = AquaTrollLogReader::default().read_csv(&mut reader).unwrap();
+
+        let gaps = log.time_gaps(Duration::from_secs(5));
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].0.to_string(), "2025-01-25 17:16:00");
+        assert_eq!(gaps[0].1.to_string(), "2025-01-25 17:20:00");
+        assert_eq!(gaps[0].2, Duration::from_secs(240));
+
+        assert_eq!(log.time_gaps(Duration::from_secs(0)).len(), 2);
+    }
+
+    #[test]
+    fn find_gaps() {
+        let mut reader = std::io::Cursor::new(
+            "Date/Time,Temp(C)
+2025/1/25 05:15:00 PM,21.6
+2025/1/25 05:15:15 PM,21.6
+2025/1/25 05:15:15 PM,21.6
+2025/1/25 05:15:50 PM,21.6
+2025/1/25 05:15:20 PM,21.7
+2025/1/25 05:15:35 PM,21.7
+",
+        );
+        let log = AquaTrollLogReader::default().read_csv(&mut reader).unwrap();
+
+        let gaps = log.find_gaps(Duration::from_secs(15), Duration::from_secs(2));
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].0.to_string(), "2025-01-25 17:15:15");
+        assert_eq!(gaps[0].1.to_string(), "2025-01-25 17:15:50");
+        assert_eq!(gaps[0].2, Duration::from_secs(35));
+        assert!(log
+            .find_gaps(Duration::from_secs(30), Duration::from_secs(5))
+            .is_empty());
+    }
+
+    #[test]
+    fn sample_interval() {
+        let mut reader = std::io::Cursor::new("Date/Time,Temp(C)\n2025/1/25 05:15:00 PM,21.6\n");
+        let mut log = AquaTrollLogReader::default().read_csv(&mut reader).unwrap();
+        assert_eq!(log.sample_interval(), None);
+
+        log.attr.insert(
+            "Log Configuration".to_string(),
+            serde_json::json!({"Sample Rate": "Days: 0 hrs: 00 mins: 00 secs: 15"}),
+        );
+        assert_eq!(log.sample_interval(), Some(Duration::from_secs(15)));
+    }
+
+    #[test]
+    fn logging_mode() {
+        let path = format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let bytes = std::fs::read(path).unwrap();
+        let (content, _, _) = UTF_16LE.decode(&bytes);
+        let reader = AquaTrollLogReader::default();
+
+        let log = reader.read_txt_str(&content).unwrap();
+        assert_eq!(log.logging_mode(), Some(LoggingMode::Event));
+
+        let content = content.replace("Type: Event", "Type: Linear");
+        let log = reader.read_txt_str(&content).unwrap();
+        assert_eq!(log.logging_mode(), Some(LoggingMode::Linear));
+        assert!(log.logging_mode().unwrap().is_fixed_interval());
+    }
+
+    #[test]
+    fn normalize() {
+        let mut reader = std::io::Cursor::new(
+            "Date/Time,Temp(C),Depth (m)
+2025/1/25 05:15:30 PM,21.7,
+2025/1/25 05:15:00 PM,21.6,
+2025/1/25 05:15:30 PM,21.8,
+",
+        );
+        let log = AquaTrollLogReader::default().read_csv(&mut reader).unwrap();
+
+        let normalized = log.normalize().unwrap();
+        assert_eq!(normalized.log_data.columns, vec!["DateTime", "Temp(C)"]);
+        assert_eq!(
+            normalized.log_data.timestamps(),
+            vec![log.log_data.timestamps()[1], log.log_data.timestamps()[0]]
+        );
+        assert!(matches!(normalized.log_data.rows[1][1], CellValue::Float64(v) if v == 21.7));
+        let summary = &normalized.attr["Normalized"];
+        assert_eq!(summary["Record Count"], 2);
+        assert_eq!(summary["Start Time"], "2025-01-25T17:15:00");
+        assert_eq!(summary["End Time"], "2025-01-25T17:15:30");
+        assert_eq!(summary["Duplicate Rows"], 1);
+        assert_eq!(summary["Dropped Columns"][0], "Depth (m)");
+
+        let unchanged = log
+            .normalize_with(&NormalizeOptions {
+                sort_by_time: false,
+                dedup_timestamps: false,
+                drop_null_columns: false,
+                normalize_column_names: false,
+                attach_metadata: false,
+            })
+            .unwrap();
+        assert_eq!(unchanged.log_data.columns, log.log_data.columns);
+        assert_eq!(unchanged.log_data.num_rows(), 3);
+        assert!(unchanged.attr.is_empty());
+    }
+
+    #[test]
+    fn devices() {
+        let html = r#"<html><table id="isi-report">
+            <tr class="dataHeader" isi-data-table="">
+            <th isi-data-column-header="DateTime">Date Time</th>
+            <th isi-data-column-header="Parameter" isi-device-serial-number="111111" isi-sensor-serial-number="999991" isi-sensor-type="58" isi-parameter-type="17" isi-unit-type="145">pH (pH) (999991)</th>
+            <th isi-data-column-header="Parameter" isi-device-serial-number="222222" isi-sensor-serial-number="999991" isi-sensor-type="58" isi-parameter-type="17" isi-unit-type="145">pH (pH) (999991)</th>
+            </tr>
+            <tr class="data" isi-data-row=""><td class="dateTime">2024-10-09 16:29:46</td><td>6.42</td><td>7.01</td></tr>
+            </table></html>"#;
+        let reader = AquaTrollLogReader::default();
+        let log = reader.read_html_str(html).unwrap();
+        assert_eq!(log.devices(), vec![111111, 222222]);
+        let columns = log.columns();
+        assert_eq!(columns[1].name, "pH (pH) (111111)");
+        assert_eq!(columns[2].name, "pH (pH) (222222)");
+        assert_eq!(columns[2].parameter, Some(Parameter::PH));
+        assert_eq!(columns[2].unit, Some(Unit::PH));
+        assert_eq!(columns[1].sensor_serial, columns[2].sensor_serial);
+        assert_eq!(columns[1].device_serial, Some(111111));
+        assert_eq!(columns[2].device_serial, Some(222222));
+
+        let sensors = reader.inventory(&mut Cursor::new(html)).unwrap();
+        assert_eq!(sensors.len(), 2);
+        assert_eq!(sensors[1].device_serial, Some(222222));
+
+        let mut file = File::open(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let log = reader.read_txt(&mut file).unwrap();
+        assert_eq!(log.devices(), vec![999995]);
+    }
+
+    #[test]
+    fn filter_time_range() {
+        let mut file = File::open(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let log = AquaTrollLogReader::default().read_txt(&mut file).unwrap();
+        let utc = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+
+        let filtered =
+            log.filter_time_range(utc("2025-01-25T08:23:00Z"), utc("2025-01-25T08:25:44Z"));
+        assert_eq!(filtered.log_data.num_rows(), 3);
+        assert_eq!(
+            filtered.log_data.timestamps().last().unwrap().to_string(),
+            "2025-01-25 16:25:29"
+        );
+        assert_eq!(filtered.log_note.unwrap().num_rows(), 1);
+
+        let filtered =
+            log.filter_time_range(utc("2025-01-25T08:24:59Z"), utc("2025-01-25T08:25:00Z"));
+        assert_eq!(filtered.log_data.num_rows(), 1);
+
+        let filtered =
+            log.filter_time_range(utc("2025-01-26T00:00:00Z"), utc("2025-01-27T00:00:00Z"));
+        assert_eq!(filtered.log_data.num_rows(), 0);
+        assert_eq!(filtered.log_data.columns, log.log_data.columns);
+    }
+
+    #[test]
+    fn select() {
+        let mut file = File::open(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let log = AquaTrollLogReader::default().read_txt(&mut file).unwrap();
+
+        let selected = log.select(&["Depth (m)", "pH (pH)"]).unwrap();
+        assert_eq!(
+            selected.log_data.columns,
+            vec!["DateTime", "pH (pH)", "Depth (m)"]
+        );
+        assert_eq!(selected.log_data.column_metadata.len(), 3);
+        assert_eq!(selected.log_data.num_rows(), log.log_data.num_rows());
+        assert!(matches!(
+            log.select(&["pH (pH)", "Missing"]),
+            Err(AquaTrollLogError::UnknownColumn(name)) if name == "Missing"
+        ));
+    }
+
+    #[test]
+    fn select_where() {
+        let mut file = File::open(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let log = AquaTrollLogReader::default().read_txt(&mut file).unwrap();
+
+        let selected = log.select_where(|d| {
+            d.unit
+                .as_ref()
+                .is_some_and(|u| u.dimension() == Dimension::Voltage)
+        });
+        assert_eq!(
+            selected.log_data.columns,
+            vec![
+                "DateTime",
+                "pH(mV) (mV)",
+                "Oxidation Reduction Potential (ORP) (mV)",
+                "External Voltage (V)",
+            ]
+        );
+        assert_eq!(selected.log_data.num_rows(), log.log_data.num_rows());
+
+        let selected = log.select_where(|d| d.sensor_serial == Some(999991));
+        assert_eq!(selected.log_data.num_columns(), 4);
+        assert_eq!(log.select_where(|_| false).log_data.columns, ["DateTime"]);
+    }
+
+    #[test]
+    fn select_where_quality() {
+        let html = r#"<html><table id="isi-report">
+            <tr class="dataHeader" isi-data-table="">
+            <th isi-data-column-header="DateTime">Date Time</th>
+            <th isi-data-column-header="Parameter" isi-sensor-serial-number="10" isi-sensor-type="58" isi-parameter-type="1" isi-unit-type="1">Temperature (C) (10)</th>
+            <th isi-data-column-header="Parameter" isi-sensor-serial-number="20" isi-sensor-type="58" isi-parameter-type="1" isi-unit-type="1">Temperature (C) (20)</th>
+            <th isi-data-column-header="Parameter">Empty</th>
+            </tr>
+            <tr class="data" isi-data-row=""><td class="dateTime">2024-10-09 16:29:46</td><td isi-data-quality="4">25.1</td><td isi-data-quality="5">25.3</td><td></td></tr>
+            </table></html>"#;
+        let log = AquaTrollLogReader::default().read_html_str(html).unwrap();
+        assert_eq!(log.log_data.columns[1], log.log_data.columns[2]);
+
+        let selected = log.select_where(|d| d.sensor_serial != Some(20));
+        let log_quality = selected.log_quality.unwrap();
+        assert_eq!(log_quality.columns, selected.log_data.columns);
+        assert_eq!(log_quality.columns.len(), 3);
+        assert_eq!(log_quality.rows[0][1], Some(DataQuality(4)));
+
+        let selected = log.select_where(|d| d.sensor_serial == Some(20));
+        let log_quality = selected.log_quality.unwrap();
+        assert_eq!(log_quality.columns, selected.log_data.columns);
+        assert_eq!(log_quality.rows[0][1], Some(DataQuality(5)));
+
+        let normalized = log
+            .normalize_with(&NormalizeOptions {
+                normalize_column_names: false,
+                ..Default::default()
+            })
+            .unwrap();
+        let log_quality = normalized.log_quality.unwrap();
+        assert_eq!(normalized.log_data.num_columns(), 3);
+        assert_eq!(log_quality.columns, normalized.log_data.columns);
+        assert_eq!(
+            log_quality.rows[0],
+            [None, Some(DataQuality(4)), Some(DataQuality(5))]
+        );
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn arrow_field_metadata_across_formats() {
+        let data_dir = format!("{}/testing/data", env!("CARGO_MANIFEST_DIR"));
+        let reader = AquaTrollLogReader::default();
+        let txt = reader
+            .read_txt(&mut File::open(format!("{data_dir}/win_situ_dump.txt")).unwrap())
+            .unwrap();
+        let html = reader
+            .read_zipped_html(
+                &mut File::open(format!(
+                    "{data_dir}/VuSitu_LiveReadings_2025-01-25_20-29-44_Device_Location.zip"
+                ))
+                .unwrap(),
+            )
+            .unwrap();
+
+        // Sensors other than of pH/ORP are of different serials in the two logs
+        let field_metadata = |log: &AquaTrollLogData, parameter: Parameter| {
+            let schema = log.to_record_batch().unwrap().schema();
+            let field = schema
+                .fields()
+                .iter()
+                .find(|f| f.metadata().get(PARAMETER_KEY) == Some(&parameter.to_string()))
+                .unwrap()
+                .clone();
+            let serial = match parameter {
+                Parameter::PH | Parameter::OxidationReductionPotential => {
+                    field.metadata().get(SENSOR_SERIAL_KEY).cloned()
+                }
+                _ => None,
+            };
+            (
+                field.metadata()[PARAMETER_KEY].clone(),
+                field.metadata()[UNIT_KEY].clone(),
+                serial,
+            )
+        };
+        for parameter in [
+            Parameter::PH,
+            Parameter::OxidationReductionPotential,
+            Parameter::DissolvedOxygenConcentration,
+            Parameter::Turbidity,
+        ] {
+            assert_eq!(
+                field_metadata(&txt, parameter),
+                field_metadata(&html, parameter),
+                "{parameter}"
+            );
+        }
+        assert_eq!(
+            field_metadata(&txt, Parameter::PH),
+            (
+                "pH".to_string(),
+                "pH".to_string(),
+                Some("999991".to_string())
+            )
+        );
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn arrow_schema_and_arrays() {
+        let mut file = File::open(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let log = AquaTrollLogReader::default().read_txt(&mut file).unwrap();
+
+        let batch = log.to_record_batch().unwrap();
+        let (schema, arrays) = log.to_arrow().unwrap();
+        assert_eq!(schema, batch.schema());
+        assert_eq!(arrays.len(), batch.num_columns());
+        for (array, column) in arrays.iter().zip(batch.columns()) {
+            assert_eq!(array.to_data(), column.to_data());
+        }
+        assert_eq!(arrays[0].len(), log.log_data.num_rows());
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_round_trip() {
+        use arrow_schema::{DataType, TimeUnit};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        use std::fs::File;
+
+        let mut file = File::open(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let log = AquaTrollLogReader::default().read_txt(&mut file).unwrap();
+
+        let mut buf = Cursor::new(vec![]);
+        log.to_parquet(&mut buf).unwrap();
+
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(buf.into_inner())).unwrap();
+        let kv = builder
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap();
+        let attr = kv.iter().find(|kv| kv.key == "attr").unwrap();
+        assert_eq!(
+            serde_json::from_str::<Map<String, Value>>(attr.value.as_ref().unwrap()).unwrap(),
+            log.attr
+        );
+        assert!(kv.iter().any(|kv| kv.key == "log_note"));
+
+        let schema = builder.schema().clone();
+        assert_eq!(
+            schema.metadata()["attr.Log Data.Record Count"],
+            log.attr["Log Data"]["Record Count"].to_string()
+        );
+        assert_eq!(
+            AquaTrollLogData::attr_from_schema(&schema).unwrap(),
+            log.attr
+        );
+        assert_eq!(schema.fields().len(), log.log_data.num_columns());
+        assert_eq!(schema.field(0).name(), "DateTime");
+        assert_eq!(
+            schema.field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Second, None)
+        );
+
+        let num_rows: usize = builder
+            .build()
+            .unwrap()
+            .map(|b| b.unwrap().num_rows())
+            .sum();
+        assert_eq!(num_rows, log.log_data.num_rows());
+    }
+
+    #[cfg(feature = "flate2")]
+    fn gzipped_txt() -> Vec<u8> {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let txt = std::fs::read(format!(
+            "{}/testing/data/win_situ_dump.txt",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(&txt).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn read_gzipped_txt() {
+        let reader = AquaTrollLogReader::default();
+        let log = reader
+            .read_gzipped_txt(&mut gzipped_txt().as_slice())
+            .unwrap();
+        assert_eq!(log.log_data.num_rows(), 5);
+        assert_eq!(log.log_note.unwrap().num_rows(), 2);
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn read_truncated_gzipped_txt() {
+        let gz = gzipped_txt();
+        let reader = AquaTrollLogReader::default();
+        let result = reader.read_gzipped_txt(&mut &gz[..gz.len() / 2]);
+        assert!(matches!(result, Err(AquaTrollLogError::UnexpectedEof)));
+    }
+
+    #[cfg(all(feature = "flate2", feature = "tar"))]
+    #[test]
+    fn tar_gz_with_txt_and_csv() {
+        use flate2::{write::GzEncoder, Compression};
+
+        let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        for name in ["win_situ_dump.txt", "win_situ_record.csv"] {
+            builder
+                .append_path_with_name(
+                    format!("{}/testing/data/{name}", env!("CARGO_MANIFEST_DIR")),
+                    format!("logs/{name}"),
+                )
+                .unwrap();
+        }
+        let mut append = |name: &str, content: &[u8]| {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_cksum();
+            builder.append_data(&mut header, name, content).unwrap();
+        };
+        append("logs/notes.md", b"Deployment notes\n");
+        append(
+            "logs/corrupt.csv",
+            b"Date/Time,Temp(C)\n2025/1/25 05:15:06 PM,warm\n",
+        );
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let reader = AquaTrollLogReader::default();
+        let logs = reader.read_tar_gz(archive.as_slice()).unwrap();
+        assert_eq!(logs.len(), 2);
+        let warnings = logs[0].warnings();
+        assert_eq!(
+            warnings[warnings.len() - 2],
+            ReadWarning::UnknownFormat("logs/notes.md".to_string())
+        );
+        assert!(matches!(
+            warnings.last(),
+            Some(ReadWarning::UnreadableEntry { name, .. }) if name == "logs/corrupt.csv"
+        ));
+        assert!(logs[0].log_note.is_some());
+        assert_eq!(logs[0].log_data.num_rows(), 5);
+        assert!(logs[1].log_note.is_none());
+        assert_eq!(logs[1].log_data.column_name(0), "DateTime");
+
+        // Archives of no entry read fail
+        let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        let content = b"Date/Time,Temp(C)\n2025/1/25 05:15:06 PM,warm\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "logs/corrupt.csv", &content[..])
+            .unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+        assert!(reader.read_tar_gz(archive.as_slice()).is_err());
+    }
 }
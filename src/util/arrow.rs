@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray,
+    TimestampMillisecondArray, TimestampSecondArray,
+};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::Timelike;
+use serde_json::{Map, Value};
+
+use super::common::{CellValue, Table, PARAMETER_KEY, UNIT_KEY};
+use crate::error::AquaTrollLogError;
+
+/// Convert a table into an Arrow record batch
+///
+/// Column types are taken from the first non-null cell of each column, timestamps are stored
+/// as `Timestamp(Second, None)`, or `Timestamp(Millisecond, None)` if any has a fraction of a
+/// second (e.g. of HTML device ticks).
+///
+/// Fields carry the column metadata, with the `parameter` and `unit` of the column if known,
+/// which are described alike of all log formats. Tables of rows of other than one cell of
+/// each column fail with `ColumnCountMismatch`.
+pub(crate) fn table_to_record_batch(table: &Table) -> Result<RecordBatch, AquaTrollLogError> {
+    if let Some((i, row)) = table
+        .rows
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.len() != table.num_columns())
+    {
+        return Err(AquaTrollLogError::ColumnCountMismatch {
+            line_number: i + 1,
+            expected: table.num_columns(),
+            found: row.len(),
+        });
+    }
+
+    let mut fields = Vec::with_capacity(table.num_columns());
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(table.num_columns());
+    let descriptors = table.column_descriptors();
+
+    for (i, name) in table.columns.iter().enumerate() {
+        let cells = table.rows.iter().map(|row| &row[i]);
+        let first_value = table
+            .rows
+            .iter()
+            .map(|row| &row[i])
+            .find(|v| !matches!(v, CellValue::Null));
+        let (data_type, array): (DataType, ArrayRef) = match first_value {
+            Some(CellValue::DateTime(_))
+                if table.rows.iter().any(
+                    |row| matches!(row[i], CellValue::DateTime(dt) if dt.nanosecond() != 0),
+                ) =>
+            {
+                (
+                    DataType::Timestamp(TimeUnit::Millisecond, None),
+                    Arc::new(
+                        cells
+                            .map(|v| match v {
+                                CellValue::DateTime(dt) => {
+                                    Ok(Some(dt.and_utc().timestamp_millis()))
+                                }
+                                CellValue::Null => Ok(None),
+                                _ => Err(AquaTrollLogError::InvalidData),
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                            .map(TimestampMillisecondArray::from)?,
+                    ),
+                )
+            }
+            Some(CellValue::DateTime(_)) => (
+                DataType::Timestamp(TimeUnit::Second, None),
+                Arc::new(
+                    cells
+                        .map(|v| match v {
+                            CellValue::DateTime(dt) => Ok(Some(dt.and_utc().timestamp())),
+                            CellValue::Null => Ok(None),
+                            _ => Err(AquaTrollLogError::InvalidData),
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(TimestampSecondArray::from)?,
+                ),
+            ),
+            Some(CellValue::Int64(_)) => (
+                DataType::Int64,
+                Arc::new(
+                    cells
+                        .map(|v| match v {
+                            CellValue::Int64(i) => Ok(Some(*i)),
+                            CellValue::Null => Ok(None),
+                            _ => Err(AquaTrollLogError::InvalidData),
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(Int64Array::from)?,
+                ),
+            ),
+            Some(CellValue::Boolean(_)) => (
+                DataType::Boolean,
+                Arc::new(
+                    cells
+                        .map(|v| match v {
+                            CellValue::Boolean(b) => Ok(Some(*b)),
+                            CellValue::Null => Ok(None),
+                            _ => Err(AquaTrollLogError::InvalidData),
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(BooleanArray::from)?,
+                ),
+            ),
+            Some(CellValue::Text(_)) => (
+                DataType::Utf8,
+                Arc::new(
+                    cells
+                        .map(|v| match v {
+                            CellValue::Text(s) => Ok(Some(s.as_str())),
+                            CellValue::Null => Ok(None),
+                            _ => Err(AquaTrollLogError::InvalidData),
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(StringArray::from)?,
+                ),
+            ),
+            Some(CellValue::Float64(_)) | Some(CellValue::Null) | None => (
+                DataType::Float64,
+                Arc::new(
+                    cells
+                        .map(|v| match v {
+                            CellValue::Float64(f) => Ok(Some(*f)),
+                            CellValue::Null => Ok(None),
+                            _ => Err(AquaTrollLogError::InvalidData),
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(Float64Array::from)?,
+                ),
+            ),
+        };
+        let mut metadata = table.column_metadata[i].clone();
+        if let Some(parameter) = &descriptors[i].parameter {
+            metadata.insert(PARAMETER_KEY.to_string(), parameter.to_string());
+        }
+        if let Some(unit) = &descriptors[i].unit {
+            metadata.insert(UNIT_KEY.to_string(), unit.to_string());
+        }
+        fields.push(Field::new(name, data_type, true).with_metadata(metadata));
+        arrays.push(array);
+    }
+
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+/// Prefix of the metadata keys of flattened attributes
+const ATTR_METADATA_PREFIX: &str = "attr.";
+
+/// Escape dots and backslashes of an attribute name of a dotted key
+fn escape_key(key: &str) -> String {
+    key.replace('\\', "\\\\").replace('.', "\\.")
+}
+
+/// Split a dotted key at unescaped dots into unescaped attribute names
+fn split_key(key: &str) -> Vec<String> {
+    let mut names = vec![String::new()];
+    let mut chars = key.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => names.last_mut().unwrap().extend(chars.next()),
+            '.' => names.push(String::new()),
+            c => names.last_mut().unwrap().push(c),
+        }
+    }
+    names
+}
+
+fn flatten_into(prefix: &str, attr: &Map<String, Value>, metadata: &mut HashMap<String, String>) {
+    for (key, value) in attr {
+        let key = format!("{prefix}{}", escape_key(key));
+        match value {
+            Value::Object(section) if !section.is_empty() => {
+                flatten_into(&format!("{key}."), section, metadata)
+            }
+            value => {
+                metadata.insert(key, value.to_string());
+            }
+        }
+    }
+}
+
+/// Flatten attributes into string key-values of Arrow and Parquet metadata
+///
+/// Nested sections are joined into dotted keys prefixed by `attr.`, e.g.
+/// `attr.Log Data.Record Count`, with dots of names escaped by a backslash. Values are stored
+/// as JSON text, so numbers and strings stay apart.
+pub(crate) fn flatten_attr(attr: &Map<String, Value>) -> HashMap<String, String> {
+    let mut metadata = HashMap::new();
+    flatten_into(ATTR_METADATA_PREFIX, attr, &mut metadata);
+    metadata
+}
+
+/// Rebuild the nested attributes of metadata flattened by `flatten_attr`, other keys are
+/// skipped
+pub(crate) fn unflatten_attr<'a>(
+    metadata: impl IntoIterator<Item = (&'a String, &'a String)>,
+) -> Result<Map<String, Value>, AquaTrollLogError> {
+    let mut entries: Vec<_> = metadata
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.strip_prefix(ATTR_METADATA_PREFIX)?, value)))
+        .collect();
+    entries.sort();
+
+    let mut attr = Map::new();
+    for (key, value) in entries {
+        let mut names = split_key(key);
+        let name = names.pop().unwrap_or_default();
+        let mut section = &mut attr;
+        for parent in names {
+            section = section
+                .entry(parent)
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .ok_or(AquaTrollLogError::InvalidData)?;
+        }
+        section.insert(name, serde_json::from_str(value)?);
+    }
+    Ok(attr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::{TableBuilder, SENSOR_SERIAL_KEY};
+    use super::*;
+
+    #[test]
+    fn record_batch_from_table() {
+        let table = TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                "Note".to_string(),
+                "Value".to_string(),
+                "Marked".to_string(),
+            ])
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:00".to_string(),
+                "Foo".to_string(),
+                "1.0".to_string(),
+                "Marked".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let batch = table_to_record_batch(&table).unwrap();
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(
+            batch.schema().field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Second, None)
+        );
+        assert_eq!(batch.schema().field(1).data_type(), &DataType::Utf8);
+        assert_eq!(batch.schema().field(2).data_type(), &DataType::Float64);
+        assert_eq!(batch.schema().field(3).data_type(), &DataType::Boolean);
+
+        let mut table = table;
+        if let CellValue::DateTime(dt) = &mut table.rows[0][0] {
+            *dt += chrono::Duration::milliseconds(500);
+        }
+        let batch = table_to_record_batch(&table).unwrap();
+        assert_eq!(
+            batch.schema().field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Millisecond, None)
+        );
+
+        table.rows[0].pop();
+        assert!(matches!(
+            table_to_record_batch(&table),
+            Err(AquaTrollLogError::ColumnCountMismatch {
+                line_number: 1,
+                expected: 4,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn attr_metadata_round_trip() {
+        let attr = serde_json::json!({
+            "Report Date": "2025/1/2 PM 12:23:23",
+            "Log Data": {"Record Count": 5, "Sensors": [{"Serial": 999991}]},
+            "Ver. 1.2": {"a\\b": true, "Empty": {}},
+        });
+        let attr = attr.as_object().unwrap();
+
+        let metadata = flatten_attr(attr);
+        assert_eq!(metadata["attr.Log Data.Record Count"], "5");
+        assert_eq!(metadata["attr.Report Date"], r#""2025/1/2 PM 12:23:23""#);
+        assert_eq!(metadata[r"attr.Ver\. 1\.2.a\\b"], "true");
+        assert_eq!(&unflatten_attr(&metadata).unwrap(), attr);
+    }
+
+    #[test]
+    fn record_batch_field_metadata() {
+        let table = TableBuilder::new()
+            .field_names(vec!["Date and Time".to_string(), "pH (pH)".to_string()])
+            .with_column_metadata(vec![
+                Default::default(),
+                [(SENSOR_SERIAL_KEY.to_string(), "999991".to_string())].into(),
+            ])
+            .try_push_row(vec!["2021/7/20 PM 12:00:00".to_string(), "7.0".to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let batch = table_to_record_batch(&table).unwrap();
+        assert!(batch.schema().field(0).metadata().is_empty());
+        assert_eq!(
+            batch.schema().field(1).metadata()[SENSOR_SERIAL_KEY],
+            "999991"
+        );
+    }
+}
@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+
+use super::common::{
+    CellValue, Table, DEVICE_SERIAL_KEY, SENSOR_MODEL_KEY, SENSOR_SERIAL_KEY, SENSOR_TYPE_KEY,
+};
+use super::param::Parameter;
+use super::sensor::SensorType;
+use super::unit::{Dimension, Unit};
+use crate::error::AquaTrollLogError;
+
+/// Parameter, unit and sensor of a column
+#[derive(Debug, Clone)]
+pub struct ColumnDescriptor {
+    pub name: String,
+    pub parameter: Option<Parameter>,
+    pub unit: Option<Unit>,
+    pub sensor_serial: Option<u64>,
+    /// Serial of the device of the sensor, telling apart columns of the same name of logs
+    /// combining devices (HTML logs)
+    pub device_serial: Option<u64>,
+    /// Type of the sensor parsed from its model (TXT logs) or of its sensor type code (HTML
+    /// logs)
+    pub sensor_type: Option<SensorType>,
+}
+
+/// Smallest or largest reading of a parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extreme {
+    Min,
+    Max,
+}
+
+/// Reference of the readings of a pressure sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureReference {
+    /// Readings relative to the atmosphere, of sensors vented through the cable
+    Vented,
+    /// Readings including the atmospheric pressure, of non-vented sensors
+    Absolute,
+}
+
+/// Split off the trailing unit group of a column name, e.g. `pH` of `pH (pH)` and `C` of
+/// `Temp(C)`
+fn unit_group(name: &str) -> Option<&str> {
+    let name = name.trim_end().strip_suffix(')')?;
+    name.rfind('(').map(|i| &name[i + 1..])
+}
+
+/// Replace the trailing unit group of a column name with the display name of `unit`
+fn with_unit_group(name: &str, unit: &Unit) -> String {
+    let prefix = name
+        .trim_end()
+        .strip_suffix(')')
+        .and_then(|name| name.rfind('(').map(|i| &name[..=i]));
+    match prefix {
+        Some(prefix) => format!("{prefix}{unit})"),
+        None => format!("{name} ({unit})"),
+    }
+}
+
+/// Split a column name into the name before a trailing group of the sensor or device serial
+/// (e.g. of `pH (pH) (999991)`) and the group
+fn split_serial_group<'a>(name: &'a str, serials: &[Option<u64>]) -> (&'a str, &'a str) {
+    serials
+        .iter()
+        .flatten()
+        .find_map(|serial| {
+            let base = name.trim_end().strip_suffix(&format!(" ({serial})"))?;
+            Some((base, &name[base.len()..]))
+        })
+        .unwrap_or((name, ""))
+}
+
+impl ColumnDescriptor {
+    /// Describe a column from its name and metadata, a trailing group of the sensor or device
+    /// serial of the name (e.g. of `pH (pH) (999991)`) is skipped
+    pub(crate) fn new(name: &str, sensor_serial: Option<u64>, device_serial: Option<u64>) -> Self {
+        let (base, _) = split_serial_group(name, &[sensor_serial, device_serial]);
+        Self {
+            name: name.to_string(),
+            parameter: base.parse().ok(),
+            unit: unit_group(base).and_then(|u| u.parse().ok()),
+            sensor_serial,
+            device_serial,
+            sensor_type: None,
+        }
+    }
+
+    /// Split the name into the name before a trailing group of the sensor or device serial
+    /// and the group
+    fn split_serial_group(&self) -> (&str, &str) {
+        split_serial_group(&self.name, &[self.sensor_serial, self.device_serial])
+    }
+}
+
+impl Table {
+    /// Describe columns by parameter, unit and sensor serial
+    ///
+    /// Parameters and units are parsed from the column names, serials and sensor types are
+    /// taken from the column metadata.
+    pub fn column_descriptors(&self) -> Vec<ColumnDescriptor> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let metadata = self.column_metadata.get(i);
+                let serial = metadata
+                    .and_then(|m| m.get(SENSOR_SERIAL_KEY))
+                    .and_then(|s| s.parse().ok());
+                let device_serial = metadata
+                    .and_then(|m| m.get(DEVICE_SERIAL_KEY))
+                    .and_then(|s| s.parse().ok());
+                ColumnDescriptor {
+                    sensor_type: metadata
+                        .and_then(|m| m.get(SENSOR_MODEL_KEY))
+                        .and_then(|s| s.parse().ok())
+                        .or_else(|| {
+                            let code = metadata?.get(SENSOR_TYPE_KEY)?.parse().ok()?;
+                            Some(SensorType::from_code(code))
+                        }),
+                    ..ColumnDescriptor::new(name, serial, device_serial)
+                }
+            })
+            .collect()
+    }
+
+    /// Distinct parameters of the columns, in column order
+    pub fn parameters(&self) -> Vec<Parameter> {
+        let mut parameters = vec![];
+        for parameter in self
+            .column_descriptors()
+            .into_iter()
+            .filter_map(|d| d.parameter)
+        {
+            if !parameters.contains(&parameter) {
+                parameters.push(parameter);
+            }
+        }
+        parameters
+    }
+
+    /// Index of the row of the smallest or largest reading of the first column of `parameter`,
+    /// the first of ties, `None` if the column has no readings
+    pub fn row_at_extreme(
+        &self,
+        parameter: Parameter,
+        which: Extreme,
+    ) -> Result<Option<usize>, AquaTrollLogError> {
+        let index = self
+            .column_descriptors()
+            .iter()
+            .position(|d| d.parameter == Some(parameter))
+            .ok_or_else(|| AquaTrollLogError::ParameterNotFound(parameter.to_string()))?;
+
+        let readings = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, row)| match row[index] {
+                CellValue::Float64(v) if !v.is_nan() => Some((i, v)),
+                _ => None,
+            });
+        let extreme = readings.reduce(|a, b| match which {
+            Extreme::Min if b.1 < a.1 => b,
+            Extreme::Max if b.1 > a.1 => b,
+            _ => a,
+        });
+        Ok(extreme.map(|(i, _)| i))
+    }
+
+    /// Names and unit groups of the columns named with a unit which isn't a known one, groups
+    /// of numbers (e.g. sensor serials) are not taken as units
+    pub(crate) fn unknown_units(&self) -> Vec<(String, String)> {
+        self.columns
+            .iter()
+            .filter_map(|name| {
+                let unit = unit_group(name)?;
+                let is_number = unit.chars().all(|c| c.is_ascii_digit());
+                (!is_number && unit.parse::<Unit>().is_err())
+                    .then(|| (name.clone(), unit.to_string()))
+            })
+            .collect()
+    }
+
+    /// Convert readings of temperature columns into the `to` unit, other columns are
+    /// untouched
+    ///
+    /// Units are taken from the column names, which are renamed to the new unit. Returns the
+    /// new names of the converted columns by their old names.
+    pub fn convert_temperatures(
+        &mut self,
+        to: &Unit,
+    ) -> Result<HashMap<String, String>, AquaTrollLogError> {
+        if to.dimension() != Dimension::Temperature {
+            return Err(Unit::Celsius.incompatible_with(to));
+        }
+
+        let mut renames = HashMap::new();
+        for (i, descriptor) in self.column_descriptors().into_iter().enumerate() {
+            let Some(from) = descriptor.unit else {
+                continue;
+            };
+            if from.dimension() != Dimension::Temperature || from == *to {
+                continue;
+            }
+
+            for row in self.rows.iter_mut() {
+                if let CellValue::Float64(v) = row[i] {
+                    row[i] = CellValue::Float64(from.convert(v, to)?);
+                }
+            }
+            let (base, serial) = descriptor.split_serial_group();
+            let name = format!("{}{serial}", with_unit_group(base, to));
+            self.columns[i] = name.clone();
+            renames.insert(descriptor.name, name);
+        }
+        Ok(renames)
+    }
+
+    /// Set readings of depth and level columns to null in rows of the sensor out of water,
+    /// where a depth is negative, or the pressure is not above the barometric pressure of
+    /// `Absolute` sensors
+    ///
+    /// Pressures of `Vented` sensors are relative to the atmosphere and aren't compared with the
+    /// barometric pressure. Barometric pressures are converted into the unit of the pressure if
+    /// both are known. Returns the number of masked rows.
+    pub fn mask_out_of_water(
+        &mut self,
+        reference: PressureReference,
+    ) -> Result<usize, AquaTrollLogError> {
+        let descriptors = self.column_descriptors();
+        let first = |parameter: Parameter| {
+            descriptors
+                .iter()
+                .position(|d| d.parameter == Some(parameter))
+        };
+        let pressures = match reference {
+            PressureReference::Absolute => {
+                first(Parameter::Pressure).zip(first(Parameter::BarometricPressure))
+            }
+            PressureReference::Vented => None,
+        };
+        let depths: Vec<_> = descriptors
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.parameter == Some(Parameter::Depth))
+            .map(|(i, _)| i)
+            .collect();
+        let levels: Vec<_> = descriptors
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| {
+                matches!(
+                    d.parameter,
+                    Some(Parameter::Depth | Parameter::DepthToWater | Parameter::SurfaceElevation)
+                )
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut masked = 0;
+        for row in self.rows.iter_mut() {
+            let above_barometric = match pressures {
+                Some((p, b)) => match (&row[p], &row[b]) {
+                    (CellValue::Float64(pressure), CellValue::Float64(barometric)) => {
+                        let barometric = match (&descriptors[b].unit, &descriptors[p].unit) {
+                            (Some(from), Some(to)) => from.convert(*barometric, to)?,
+                            _ => *barometric,
+                        };
+                        *pressure <= barometric
+                    }
+                    _ => false,
+                },
+                None => false,
+            };
+            let negative_depth = depths
+                .iter()
+                .any(|&i| matches!(row[i], CellValue::Float64(v) if v < 0.0));
+
+            if above_barometric || negative_depth {
+                for &i in &levels {
+                    row[i] = CellValue::Null;
+                }
+                masked += 1;
+            }
+        }
+        Ok(masked)
+    }
+
+    /// Rename columns of known parameters by the short name of the parameter and the unit
+    /// group of the name, e.g. `ORP (mV)` of `Oxidation Reduction Potential (ORP) (mV)`
+    ///
+    /// Returns the new names of the renamed columns by their old names.
+    pub fn compact_column_names(&mut self) -> HashMap<String, String> {
+        let mut renames = HashMap::new();
+        for (i, descriptor) in self.column_descriptors().into_iter().enumerate() {
+            let Some(parameter) = descriptor.parameter else {
+                continue;
+            };
+            let (base, serial) = descriptor.split_serial_group();
+            let name = match unit_group(base) {
+                Some(unit) => format!("{} ({unit}){serial}", parameter.short_name()),
+                None => format!("{}{serial}", parameter.short_name()),
+            };
+            if name != descriptor.name {
+                self.columns[i] = name.clone();
+                renames.insert(descriptor.name, name);
+            }
+        }
+        renames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_temperature_columns() {
+        let mut table = super::super::common::TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                "Temperature (F)".to_string(),
+                "Temp(C)".to_string(),
+                "Depth (ft)".to_string(),
+            ])
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:00".to_string(),
+                "212".to_string(),
+                "25".to_string(),
+                "3.5".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let renames = table.convert_temperatures(&Unit::Celsius).unwrap();
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames["Temperature (F)"], "Temperature (°C)");
+        assert_eq!(
+            table.columns,
+            vec!["DateTime", "Temperature (°C)", "Temp(C)", "Depth (ft)"]
+        );
+        assert!(matches!(table.rows[0][1], CellValue::Float64(v) if (v - 100.0).abs() < 1e-9));
+        assert!(matches!(table.rows[0][2], CellValue::Float64(v) if v == 25.0));
+        assert!(matches!(table.rows[0][3], CellValue::Float64(v) if v == 3.5));
+
+        table.convert_temperatures(&Unit::Kelvin).unwrap();
+        assert_eq!(table.columns[2], "Temp(°K)");
+        assert!(matches!(table.rows[0][2], CellValue::Float64(v) if (v - 298.15).abs() < 1e-9));
+
+        assert!(matches!(
+            table.convert_temperatures(&Unit::Meters),
+            Err(AquaTrollLogError::IncompatibleUnits { .. })
+        ));
+    }
+
+    #[test]
+    fn mask_out_of_water() {
+        let table = super::super::common::TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                "Pressure (PSI)".to_string(),
+                "Barometric Pressure (mm Hg)".to_string(),
+                "Depth (m)".to_string(),
+            ])
+            // Below the waterline
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:00".to_string(),
+                "16.0".to_string(),
+                "760".to_string(),
+                "0.9".to_string(),
+            ])
+            .unwrap()
+            // Pressure below the barometric pressure of 14.7 PSI
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:30".to_string(),
+                "14.6".to_string(),
+                "760".to_string(),
+                "0.07".to_string(),
+            ])
+            .unwrap()
+            // Negative depth
+            .try_push_row(vec![
+                "2021/7/20 PM 12:01:00".to_string(),
+                "---".to_string(),
+                "760".to_string(),
+                "-0.1".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let mut absolute = table.clone();
+        assert_eq!(
+            absolute
+                .mask_out_of_water(PressureReference::Absolute)
+                .unwrap(),
+            2
+        );
+        assert!(matches!(absolute.rows[0][3], CellValue::Float64(v) if v == 0.9));
+        assert!(matches!(absolute.rows[1][3], CellValue::Null));
+        assert!(matches!(absolute.rows[2][3], CellValue::Null));
+        assert!(matches!(absolute.rows[1][1], CellValue::Float64(v) if v == 14.6));
+
+        // Pressures of vented sensors are gauge pressures
+        let mut vented = table;
+        assert_eq!(
+            vented.mask_out_of_water(PressureReference::Vented).unwrap(),
+            1
+        );
+        assert!(matches!(vented.rows[1][3], CellValue::Float64(v) if v == 0.07));
+        assert!(matches!(vented.rows[2][3], CellValue::Null));
+        assert!(matches!(vented.rows[2][2], CellValue::Float64(v) if v == 760.0));
+    }
+
+    #[test]
+    fn compact_names() {
+        let mut table = super::super::common::TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                "Rhodamine WT Fluorescence Intensity (RFU)".to_string(),
+                "Oxidation Reduction Potential (ORP) (mV)".to_string(),
+                "Eh".to_string(),
+                "Temp(C)".to_string(),
+            ])
+            .try_build()
+            .unwrap();
+
+        let renames = table.compact_column_names();
+        assert_eq!(renames.len(), 2);
+        assert_eq!(
+            table.columns,
+            vec!["DateTime", "RhodWT_FI (RFU)", "ORP (mV)", "Eh", "Temp(C)"]
+        );
+        assert_eq!(
+            renames["Oxidation Reduction Potential (ORP) (mV)"],
+            "ORP (mV)"
+        );
+    }
+
+    #[test]
+    fn describe_column() {
+        let column = ColumnDescriptor::new("Actual Conductivity (µS/cm)", Some(999997), None);
+        assert!(matches!(
+            column.parameter,
+            Some(Parameter::ActualConductivity)
+        ));
+        assert!(matches!(column.unit, Some(Unit::MicrosiemensPerCentimeter)));
+        assert_eq!(column.sensor_serial, Some(999997));
+
+        let column = ColumnDescriptor::new("Temp(C)", None, None);
+        assert!(column.parameter.is_none());
+        assert!(matches!(column.unit, Some(Unit::Celsius)));
+
+        let column = ColumnDescriptor::new("DO(%sat)(%Sat)", None, None);
+        assert!(matches!(
+            column.parameter,
+            Some(Parameter::DissolvedOxygenPercentSaturation)
+        ));
+        assert!(matches!(
+            column.unit,
+            Some(Unit::DissolvedOxygenPercentSaturation)
+        ));
+        let column = ColumnDescriptor::new("DO(con)(mg/L)", None, None);
+        assert!(matches!(
+            column.parameter,
+            Some(Parameter::DissolvedOxygenConcentration)
+        ));
+
+        let column = ColumnDescriptor::new("DateTime", None, None);
+        assert!(column.parameter.is_none() && column.unit.is_none());
+    }
+}
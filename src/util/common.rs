@@ -1,18 +1,80 @@
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
-use chrono::NaiveDateTime;
-use serde::ser::SerializeSeq;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use encoding_rs::Encoding;
 use serde::Serialize;
 use serde_json::{Map, Value};
 
+use super::csv_reader::CsvReadOptions;
+use super::quality::DataQuality;
+use super::unit::Unit;
 use crate::error::AquaTrollLogError;
 
+/// Replace the Traditional Chinese `上午`/`下午` markers of zh-TW exports with `AM`/`PM`, and
+/// full-width spaces with single ASCII spaces
+fn normalize_am_pm_markers(datetime: &str) -> String {
+    datetime
+        .replace("上午", " AM ")
+        .replace("下午", " PM ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Position of the AM/PM marker of a timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AmPmPosition {
+    /// e.g. `2021/7/20 PM 12:00:00` of zh-TW exports
+    BeforeTime,
+    /// e.g. `2025/1/26 05:15:06 PM` of en-US exports
+    AfterTime,
+}
+
+/// Detect whether the AM/PM marker of a timestamp precedes or follows the time, `None` for
+/// 24-hour timestamps
+fn am_pm_position(datetime: &str) -> Option<AmPmPosition> {
+    let tokens: Vec<_> = datetime.split_whitespace().collect();
+    let marker = tokens
+        .iter()
+        .position(|t| t.eq_ignore_ascii_case("AM") || t.eq_ignore_ascii_case("PM"))?;
+    let time = tokens.iter().position(|t| t.contains(':'))?;
+    Some(if marker < time {
+        AmPmPosition::BeforeTime
+    } else {
+        AmPmPosition::AfterTime
+    })
+}
+
+/// Parse a timestamp of the logs, e.g. `2021/7/20 PM 12:00:00`, `2025/1/26 05:15:06 PM` and
+/// `2025-01-25 16:24:59`
+///
+/// The date is `/` or `-` separated, the time is of 12 hours if an AM/PM marker (or the
+/// `上午`/`下午` markers of zh-TW exports) precedes or follows it, of 24 hours otherwise.
 pub(crate) fn parse_datetime_str(datetime: &str) -> Result<NaiveDateTime, AquaTrollLogError> {
-    Ok(
-        NaiveDateTime::parse_from_str(datetime, "%Y/%-m/%-d %p %I:%M:%S")
-            .or_else(|_| NaiveDateTime::parse_from_str(datetime, "%Y/%-m/%-d %I:%M:%S %p"))
-            .or_else(|_| NaiveDateTime::parse_from_str(datetime, "%Y-%-m-%-d %H:%M:%S"))?,
-    )
+    let normalized;
+    let datetime = if datetime.is_ascii() {
+        datetime
+    } else {
+        normalized = normalize_am_pm_markers(datetime);
+        normalized.as_str()
+    };
+
+    let date_format = if datetime.contains('/') {
+        "%Y/%-m/%-d"
+    } else {
+        "%Y-%-m-%-d"
+    };
+    let time_format = match am_pm_position(datetime) {
+        Some(AmPmPosition::BeforeTime) => "%p %I:%M:%S",
+        Some(AmPmPosition::AfterTime) => "%I:%M:%S %p",
+        None => "%H:%M:%S",
+    };
+    Ok(NaiveDateTime::parse_from_str(
+        datetime,
+        &format!("{date_format} {time_format}"),
+    )?)
 }
 
 pub(crate) fn parse_datetime_with_format(
@@ -22,6 +84,151 @@ pub(crate) fn parse_datetime_with_format(
     NaiveDateTime::parse_from_str(datetime, format).map_err(Into::into)
 }
 
+/// Canonical name of the timestamp column of all tables
+///
+/// Timestamp headers of the log formats (`Date and Time`, `Date Time`, `Date/Time`) are all
+/// renamed to this.
+pub const DATETIME_COLUMN: &str = "DateTime";
+
+/// Column metadata key of the sensor serial number
+pub const SENSOR_SERIAL_KEY: &str = "sensor_serial";
+/// Arrow field metadata key of the parameter of a column, e.g. `pH`
+pub const PARAMETER_KEY: &str = "parameter";
+/// Arrow field metadata key of the unit of a column, e.g. `mV`
+pub const UNIT_KEY: &str = "unit";
+/// Column metadata key of the serial number of the device of the sensor (HTML logs)
+pub const DEVICE_SERIAL_KEY: &str = "device_serial";
+/// Column metadata key of the sensor model, e.g. `pH/ORP` (TXT logs)
+pub const SENSOR_MODEL_KEY: &str = "sensor_model";
+/// Column metadata key of the sensor type code, e.g. `58` (HTML logs)
+pub const SENSOR_TYPE_KEY: &str = "sensor_type";
+
+/// Header names of timestamp columns used by the log formats
+const DATETIME_HEADERS: &[&str] = &["Date and Time", "Date Time", "Date/Time", DATETIME_COLUMN];
+
+/// Check if a column header is the timestamp column
+pub(crate) fn is_datetime_header(name: &str) -> bool {
+    DATETIME_HEADERS.contains(&name)
+}
+
+/// Check if a column header is a known header name, ignoring surrounding whitespace and case
+/// if `lenient`
+fn header_matches(name: &str, known: &str, lenient: bool) -> bool {
+    if lenient {
+        name.trim().to_lowercase() == known.to_lowercase()
+    } else {
+        name == known
+    }
+}
+
+/// Check if a column header is the timestamp column, ignoring case too if `lenient`
+pub(crate) fn is_datetime_header_with(name: &str, lenient: bool) -> bool {
+    is_datetime_header(name)
+        || (lenient
+            && DATETIME_HEADERS
+                .iter()
+                .any(|known| header_matches(name, known, true)))
+}
+
+/// Windows time zone names without daylight saving time, as written by WinSitu
+const TIME_ZONE_NAMES: &[(&str, i32)] = &[
+    ("Coordinated Universal Time", 0),
+    ("UTC", 0),
+    ("India Standard Time", 5 * 3600 + 1800),
+    ("China Standard Time", 8 * 3600),
+    ("中國標準時間", 8 * 3600),
+    ("Singapore Standard Time", 8 * 3600),
+    ("Taipei Standard Time", 8 * 3600),
+    ("台北標準時間", 8 * 3600),
+    ("Tokyo Standard Time", 9 * 3600),
+    ("Korea Standard Time", 9 * 3600),
+];
+
+/// Common time zone abbreviations of timestamps, e.g. `2024-10-09 16:29:44 JST`
+///
+/// Ambiguous abbreviations aren't listed and rejected, e.g. `CST` of China or US Central
+/// Standard Time, `IST` of India, Irish or Israel Standard Time, `CDT` of US Central or Cuba
+/// Daylight Time, `MST` of US Mountain or Malaysia Standard Time and `PST` of US Pacific or
+/// Philippine Standard Time.
+const TIME_ZONE_ABBREVIATIONS: &[(&str, i32)] = &[
+    ("GMT", 0),
+    ("HKT", 8 * 3600),
+    ("SGT", 8 * 3600),
+    ("JST", 9 * 3600),
+    ("KST", 9 * 3600),
+    ("EST", -5 * 3600),
+    ("EDT", -4 * 3600),
+    ("MDT", -6 * 3600),
+    ("PDT", -7 * 3600),
+];
+
+/// Offset of a time zone abbreviation, e.g. `JST`
+fn parse_time_zone_abbreviation(abbreviation: &str) -> Option<FixedOffset> {
+    let (_, secs) = TIME_ZONE_ABBREVIATIONS
+        .iter()
+        .find(|(name, _)| *name == abbreviation)?;
+    FixedOffset::east_opt(*secs)
+}
+
+/// Split a timestamp into the timestamp before a trailing time zone abbreviation and the
+/// offset of the abbreviation
+fn split_time_zone_abbreviation(datetime: &str) -> Option<(&str, FixedOffset)> {
+    let (datetime, abbreviation) = datetime.trim_end().rsplit_once(' ')?;
+    Some((datetime, parse_time_zone_abbreviation(abbreviation)?))
+}
+
+/// Parse a time zone name (e.g. `Taipei Standard Time`), abbreviation (e.g. `JST`) or offset
+/// (e.g. `UTC+08:00`, `-05:00`, `08:00:00`) into a fixed offset
+pub(crate) fn parse_time_zone(time_zone: &str) -> Option<FixedOffset> {
+    let time_zone = time_zone.trim();
+    if let Some((_, secs)) = TIME_ZONE_NAMES.iter().find(|(name, _)| *name == time_zone) {
+        return FixedOffset::east_opt(*secs);
+    }
+    if let Some(offset) = parse_time_zone_abbreviation(time_zone) {
+        return Some(offset);
+    }
+
+    let offset = time_zone
+        .strip_prefix("UTC")
+        .or_else(|| time_zone.strip_prefix("GMT"))
+        .unwrap_or(time_zone);
+    let (sign, offset) = match offset.strip_prefix('-') {
+        Some(offset) => (-1, offset),
+        None => (1, offset.strip_prefix('+').unwrap_or(offset)),
+    };
+    let mut parts = offset.split(':').map(|p| p.parse::<i32>().ok());
+    let hours = parts.next()??;
+    let minutes = parts.next().unwrap_or(Some(0))?;
+    let seconds = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+/// Parse a sample rate of TXT logs, e.g. `Days: 0 hrs: 00 mins: 00 secs: 15`
+pub(crate) fn parse_sample_rate(sample_rate: &str) -> Option<std::time::Duration> {
+    let tokens: Vec<&str> = sample_rate.split_whitespace().collect();
+    if tokens.is_empty() || !tokens.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let mut secs = 0u64;
+    for pair in tokens.chunks(2) {
+        let scale = match pair[0].strip_suffix(':')?.to_ascii_lowercase().as_str() {
+            "days" | "day" => 86400,
+            "hrs" | "hr" | "hours" | "hour" => 3600,
+            "mins" | "min" | "minutes" | "minute" => 60,
+            "secs" | "sec" | "seconds" | "second" => 1,
+            _ => return None,
+        };
+        secs = secs.checked_add(pair[1].parse::<u64>().ok()?.checked_mul(scale)?)?;
+    }
+
+    Some(std::time::Duration::from_secs(secs))
+}
+
 pub type DateTimeParserFnRef = Rc<dyn Fn(&str) -> Result<NaiveDateTime, AquaTrollLogError>>;
 #[derive(Clone)]
 pub struct DateTimeParserFn(DateTimeParserFnRef);
@@ -62,12 +269,153 @@ impl From<DateTimeParserFnRef> for DateTimeParser {
     }
 }
 
+/// Options shared by the log readers
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ReadOptions {
+    pub datetime_parser: DateTimeParser,
+    /// Serial numbers of sensors to be read, all sensors are read if not set
+    pub sensor_filter: Option<Vec<u64>>,
+    /// HTML cells flagged with a data quality below this are read as null
+    pub null_below_quality: Option<DataQuality>,
+    /// Time zone of timestamps, overrides the time zone detected from the log
+    pub timezone: Option<FixedOffset>,
+    /// Parse attribute values into numbers, booleans and date times instead of strings
+    pub typed_attrs: bool,
+    /// Read infinite and overflowed readings as null instead of infinity
+    pub infinite_as_null: bool,
+    /// Column and value marking note rows of tables with interleaved notes and data
+    pub note_discriminator: Option<(String, String)>,
+    /// New names of log data columns by their names in the log
+    pub column_renames: HashMap<String, String>,
+    /// Read rows of TXT tables not spanning all columns, missing cells are left empty
+    pub lenient_columns: bool,
+    /// Fail on a `Record Count` of TXT logs not matching the rows read, instead of a warning
+    pub strict_record_count: bool,
+    /// Layout of CSV files and handling of rows of a different length than the header
+    pub csv: CsvReadOptions,
+    /// Unit to convert readings of temperature columns into
+    pub temperature_unit: Option<Unit>,
+    /// Split TXT rows of the timestamp merged with the first reading, of wide readings
+    pub recovery: bool,
+    /// Types of log data columns by name, overriding the types inferred from the names
+    pub column_types: HashMap<String, ColumnType>,
+    /// Name of the timestamp column, `DateTime` if not set
+    pub datetime_column_name: Option<String>,
+    /// Read timestamps of HTML logs from the `isi-timestamp` device ticks
+    pub tick_timestamps: bool,
+    /// Encoding of TXT and CSV logs, overrides the encoding detected from the file
+    pub encoding: Option<&'static Encoding>,
+    /// Match CSV headers against the timestamp and text header names ignoring whitespace and
+    /// case
+    pub lenient_header_match: bool,
+    /// Read logs of no data rows, which fail with `EmptyTable` otherwise
+    pub allow_empty: bool,
+    /// Skip the log notes of TXT logs, `log_note` is left `None`
+    pub skip_note: bool,
+    /// Name log data columns of known parameters by the short names of the parameters
+    pub compact_column_names: bool,
+    /// Append the sensor serial to the names of TXT log data columns, e.g. `pH (pH) (999991)`
+    pub serial_column_names: bool,
+    /// Rewrite float log data columns of only integral values into integer columns
+    pub downcast_integer_columns: bool,
+}
+
+impl ReadOptions {
+    /// Check if the column of the sensor should be read
+    ///
+    /// Columns without a sensor serial (e.g. `DateTime` and `Marked`) are always read.
+    pub fn accepts_sensor(&self, serial: Option<u64>) -> bool {
+        match (&self.sensor_filter, serial) {
+            (Some(filter), Some(serial)) => filter.contains(&serial),
+            _ => true,
+        }
+    }
+
+    /// Types of columns overriding the types inferred from the names, the column of the note
+    /// discriminator is read as text
+    pub fn column_types(&self) -> HashMap<String, ColumnType> {
+        let mut column_types = self.column_types.clone();
+        if let Some((column, _)) = &self.note_discriminator {
+            column_types.insert(column.clone(), ColumnType::Text);
+        }
+        column_types
+    }
+
+    /// JSON value of an attribute, typed if `typed_attrs` is set
+    pub fn attr_value(&self, value: &str) -> Value {
+        if self.typed_attrs {
+            typed_attr_value(value, self.timezone)
+        } else {
+            Value::String(value.to_string())
+        }
+    }
+}
+
+/// Parse a date time attribute, optionally followed by a time zone name
+fn parse_attr_datetime(value: &str) -> Option<(NaiveDateTime, Option<FixedOffset>)> {
+    if let Ok(dt) = parse_datetime_str(value) {
+        return Some((dt, None));
+    }
+
+    value.match_indices(' ').find_map(|(i, _)| {
+        let dt = parse_datetime_str(&value[..i]).ok()?;
+        let tz = parse_time_zone(&value[i + 1..])?;
+        Some((dt, Some(tz)))
+    })
+}
+
+/// Convert an attribute value into a JSON number, boolean or RFC 3339 date time when it looks
+/// like one, otherwise a string
+///
+/// Date times without a time zone take `timezone`, and are written without an offset if it's
+/// not given either. Integers with leading zeros are kept as strings.
+fn typed_attr_value(value: &str, timezone: Option<FixedOffset>) -> Value {
+    let value = value.trim();
+
+    let has_leading_zero = value.len() > 1 && value.starts_with('0') && !value.starts_with("0.");
+    if !has_leading_zero {
+        if let Ok(v) = value.parse::<i64>() {
+            return Value::Number(v.into());
+        }
+        if value.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') {
+            if let Some(v) = value
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+            {
+                return Value::Number(v);
+            }
+        }
+    }
+
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "enabled" => return Value::Bool(true),
+        "false" | "disabled" => return Value::Bool(false),
+        _ => {}
+    }
+
+    if let Some((dt, tz)) = parse_attr_datetime(value) {
+        let datetime = match tz.or(timezone) {
+            Some(tz) => dt.and_local_timezone(tz).single().map(|dt| dt.to_rfc3339()),
+            None => Some(dt.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        };
+        if let Some(datetime) = datetime {
+            return Value::String(datetime);
+        }
+    }
+
+    Value::String(value.to_string())
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum CellValue {
     DateTime(NaiveDateTime),
     Float64(f64),
+    Int64(i64),
+    Boolean(bool),
     Text(String),
+    Null,
 }
 
 impl std::fmt::Display for CellValue {
@@ -75,7 +423,10 @@ impl std::fmt::Display for CellValue {
         match self {
             CellValue::DateTime(dt) => write!(f, "{}", dt.format("%Y-%m-%d %H:%M:%S")),
             CellValue::Float64(v) => write!(f, "{v}"),
+            CellValue::Int64(v) => write!(f, "{v}"),
+            CellValue::Boolean(b) => write!(f, "{b}"),
             CellValue::Text(s) => write!(f, "{s}"),
+            CellValue::Null => Ok(()),
         }
     }
 }
@@ -84,8 +435,16 @@ impl std::fmt::Display for CellValue {
 pub struct Table {
     pub columns: Vec<String>,
     pub rows: Vec<Vec<CellValue>>,
+    /// Time zone of the local timestamps, if known
+    pub timezone: Option<FixedOffset>,
+    /// Metadata of each column, e.g. the serial number of the sensor under
+    /// `SENSOR_SERIAL_KEY`
+    pub column_metadata: Vec<HashMap<String, String>>,
 }
 
+/// Cell of the values missing of short rows
+const NULL_CELL: &CellValue = &CellValue::Null;
+
 impl Table {
     pub fn num_columns(&self) -> usize {
         self.columns.len()
@@ -99,6 +458,279 @@ impl Table {
         &self.columns[index]
     }
 
+    /// Values of the column at `index`, cells missing of short rows are null
+    pub fn column_values(&self, index: usize) -> impl Iterator<Item = &CellValue> {
+        self.rows
+            .iter()
+            .map(move |row| row.get(index).unwrap_or(NULL_CELL))
+    }
+
+    /// Iterate over columns as `(name, values)` pairs
+    pub fn iter_columns(&self) -> impl Iterator<Item = (&str, Vec<&CellValue>)> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), self.column_values(i).collect()))
+    }
+
+    /// Index of the timestamp column, the first column holding date time values
+    ///
+    /// Tables without any timestamp, e.g. of no rows, fall back to the first column named
+    /// `DateTime` or a known timestamp header.
+    pub fn datetime_column(&self) -> Option<usize> {
+        (0..self.num_columns())
+            .find(|&i| {
+                self.rows
+                    .iter()
+                    .any(|row| matches!(row.get(i), Some(CellValue::DateTime(_))))
+            })
+            .or_else(|| self.columns.iter().position(|c| is_datetime_header(c)))
+    }
+
+    /// Timestamps of rows, rows without a timestamp are skipped
+    pub fn timestamps(&self) -> Vec<NaiveDateTime> {
+        let Some(index) = self.datetime_column() else {
+            return vec![];
+        };
+
+        self.rows
+            .iter()
+            .filter_map(|row| match row.get(index) {
+                Some(CellValue::DateTime(dt)) => Some(*dt),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Indices of rows sorted by timestamp, rows without a timestamp are kept at the end
+    pub(crate) fn time_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.num_rows()).collect();
+        if let Some(index) = self.datetime_column() {
+            order.sort_by_key(|&i| match self.rows[i].get(index) {
+                Some(CellValue::DateTime(dt)) => (false, Some(*dt)),
+                _ => (true, None),
+            });
+        }
+        order
+    }
+
+    /// Indices of rows not repeating the timestamp of an earlier row
+    pub(crate) fn unique_time_rows(&self) -> Vec<usize> {
+        let Some(index) = self.datetime_column() else {
+            return (0..self.num_rows()).collect();
+        };
+
+        let mut seen = std::collections::HashSet::new();
+        (0..self.num_rows())
+            .filter(|&i| match self.rows[i].get(index) {
+                Some(CellValue::DateTime(dt)) => seen.insert(*dt),
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// Indices of rows of timestamps within `[start, end)` in UTC, timestamps of tables of
+    /// unknown time zones are taken as UTC, rows without a timestamp are skipped
+    pub(crate) fn time_range_rows(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<usize> {
+        let Some(index) = self.datetime_column() else {
+            return vec![];
+        };
+        let offset = self.timezone.map_or(0, |tz| tz.local_minus_utc());
+
+        (0..self.num_rows())
+            .filter(|&i| match self.rows[i].get(index) {
+                Some(CellValue::DateTime(dt)) => {
+                    let dt = (*dt - chrono::Duration::seconds(offset.into())).and_utc();
+                    start <= dt && dt < end
+                }
+                _ => false,
+            })
+            .collect()
+    }
+
+    /// Keep only the rows at `indices`, in the given order
+    pub(crate) fn select_rows(&mut self, indices: &[usize]) {
+        self.rows = indices.iter().map(|&i| self.rows[i].clone()).collect();
+    }
+
+    /// Sort rows by timestamp, rows without a timestamp are kept at the end
+    pub fn sort_by_time(&mut self) {
+        let order = self.time_order();
+        self.select_rows(&order);
+    }
+
+    /// Remove rows repeating the timestamp of an earlier row, returns the number of removed rows
+    pub fn dedup_timestamps(&mut self) -> usize {
+        let num_rows = self.num_rows();
+        let rows = self.unique_time_rows();
+        self.select_rows(&rows);
+        num_rows - self.num_rows()
+    }
+
+    /// Fill null cells of numeric columns by `strategy`, in row order
+    ///
+    /// Leading nulls are left unfilled forward and trailing nulls backward.
+    pub fn fill_missing(&mut self, strategy: FillStrategy) {
+        for i in 0..self.num_columns() {
+            if !self
+                .column_values(i)
+                .any(|v| matches!(v, CellValue::Float64(_)))
+            {
+                continue;
+            }
+
+            let order: Vec<usize> = match strategy {
+                FillStrategy::Backward => (0..self.num_rows()).rev().collect(),
+                FillStrategy::Forward | FillStrategy::Value(_) => (0..self.num_rows()).collect(),
+            };
+            let mut last = None;
+            for j in order {
+                let Some(cell) = self.rows[j].get_mut(i) else {
+                    continue;
+                };
+                match (&cell, strategy) {
+                    (CellValue::Null, FillStrategy::Value(v)) => *cell = CellValue::Float64(v),
+                    (CellValue::Null, _) => {
+                        if let Some(v) = last {
+                            *cell = CellValue::Float64(v);
+                        }
+                    }
+                    (CellValue::Float64(v), _) => last = Some(*v),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Remove columns without any non-null value, returns names of the removed columns
+    pub fn drop_null_columns(&mut self) -> Vec<String> {
+        let keep = self.non_null_columns();
+        self.retain_columns(&keep)
+    }
+
+    /// Flags of columns with any non-null value
+    pub(crate) fn non_null_columns(&self) -> Vec<bool> {
+        (0..self.num_columns())
+            .map(|i| self.column_values(i).any(|v| !matches!(v, CellValue::Null)))
+            .collect()
+    }
+
+    /// Rewrite float columns of only integral values within the range of `i64` into integer
+    /// columns, e.g. of counts, returns names of the rewritten columns
+    pub fn downcast_integer_columns(&mut self) -> Vec<String> {
+        let integral = |v: &CellValue| match v {
+            CellValue::Float64(f) => {
+                f.fract() == 0.0 && *f >= i64::MIN as f64 && *f < i64::MAX as f64
+            }
+            v => matches!(v, CellValue::Null),
+        };
+
+        let mut downcast = vec![];
+        for i in 0..self.num_columns() {
+            if !self
+                .column_values(i)
+                .any(|v| matches!(v, CellValue::Float64(_)))
+                || !self.column_values(i).all(integral)
+            {
+                continue;
+            }
+            for cell in self.rows.iter_mut().filter_map(|row| row.get_mut(i)) {
+                if let CellValue::Float64(f) = *cell {
+                    *cell = CellValue::Int64(f as i64);
+                }
+            }
+            downcast.push(self.columns[i].clone());
+        }
+        downcast
+    }
+
+    /// Rename columns by the `renames` map of old to new names, returns the old names not
+    /// found in the table
+    pub fn rename_columns(&mut self, renames: &HashMap<String, String>) -> Vec<String> {
+        let mut missing: Vec<String> = renames
+            .keys()
+            .filter(|old| !self.columns.contains(old))
+            .cloned()
+            .collect();
+        missing.sort();
+
+        for name in self.columns.iter_mut() {
+            if let Some(new_name) = renames.get(name) {
+                *name = new_name.clone();
+            }
+        }
+        missing
+    }
+
+    /// Keep only the columns flagged in `keep`, returns names of the removed columns
+    pub(crate) fn retain_columns(&mut self, keep: &[bool]) -> Vec<String> {
+        if keep.iter().all(|&k| k) {
+            return vec![];
+        }
+
+        let mut dropped = vec![];
+        let mut keep_iter = keep.iter();
+        self.columns.retain(|name| {
+            let k = *keep_iter.next().unwrap();
+            if !k {
+                dropped.push(name.clone());
+            }
+            k
+        });
+        let mut keep_iter = keep.iter();
+        self.column_metadata.retain(|_| *keep_iter.next().unwrap());
+        for row in self.rows.iter_mut() {
+            let mut keep_iter = keep.iter();
+            row.retain(|_| *keep_iter.next().unwrap());
+        }
+        dropped
+    }
+
+    /// Move rows with `value` in the `discriminator` column out into a note table
+    ///
+    /// The note table keeps the timestamp and `Note` columns, these and the discriminator
+    /// column are removed from this table. Returns `None` if there's no discriminator column.
+    pub(crate) fn split_notes(&mut self, discriminator: &str, value: &str) -> Option<Table> {
+        let index = self.columns.iter().position(|c| c == discriminator)?;
+        let is_note_row = |row: &Vec<CellValue>| matches!(row.get(index), Some(CellValue::Text(s)) if s.trim() == value);
+        let datetime = self.datetime_column();
+
+        let note_columns: Vec<bool> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| Some(i) == datetime || c == "Note")
+            .collect();
+        let (note_rows, data_rows): (Vec<_>, Vec<_>) =
+            self.rows.drain(..).partition(|row| is_note_row(row));
+
+        let mut notes = Table {
+            columns: self.columns.clone(),
+            rows: note_rows,
+            timezone: self.timezone,
+            column_metadata: self.column_metadata.clone(),
+        };
+        notes.retain_columns(&note_columns);
+
+        self.rows = data_rows;
+        let data_columns: Vec<bool> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, _)| i != index && (Some(i) == datetime || !note_columns[i]))
+            .collect();
+        self.retain_columns(&data_columns);
+
+        Some(notes)
+    }
+
+    /// Trim column names and collapse inner runs of whitespace into a single space
+    pub fn normalize_column_names(&mut self) {
+        for name in self.columns.iter_mut() {
+            *name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+    }
+
     /// Write the table as CSV to any `io::Write` destination.
     pub fn write_csv<W: std::io::Write>(&self, writer: W) -> Result<(), csv::Error> {
         let mut csv_writer = csv::Writer::from_writer(writer);
@@ -110,47 +742,372 @@ impl Table {
         csv_writer.flush()?;
         Ok(())
     }
-}
 
-impl Serialize for Table {
-    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let mut seq = serializer.serialize_seq(Some(self.rows.len()))?;
+    /// Write the table as CSV with timestamps converted into the `tz` time zone and formatted
+    /// with `format`
+    ///
+    /// Timestamps are taken as UTC if the time zone of the table is unknown.
+    pub fn write_csv_in_timezone<W: std::io::Write, Tz: TimeZone>(
+        &self,
+        writer: W,
+        tz: &Tz,
+        format: &str,
+    ) -> Result<(), csv::Error>
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record(&self.columns)?;
         for row in &self.rows {
-            let obj: Map<String, Value> = self
-                .columns
+            let fields: Vec<String> = row
                 .iter()
-                .zip(row.iter())
-                .map(|(col, val)| {
-                    let v = match val {
-                        CellValue::DateTime(dt) => {
-                            Value::String(dt.format("%Y-%m-%dT%H:%M:%S").to_string())
-                        }
-                        CellValue::Float64(f) => serde_json::Number::from_f64(*f)
-                            .map(Value::Number)
-                            .unwrap_or(Value::Null),
-                        CellValue::Text(s) => Value::String(s.clone()),
-                    };
-                    (col.clone(), v)
+                .map(|v| match v {
+                    CellValue::DateTime(dt) => {
+                        let dt = match self.timezone {
+                            Some(offset) => dt.and_local_timezone(offset).single(),
+                            None => Some(Utc.from_utc_datetime(dt).fixed_offset()),
+                        };
+                        dt.map(|dt| dt.with_timezone(tz).format(format).to_string())
+                            .unwrap_or_default()
+                    }
+                    v => v.to_string(),
                 })
                 .collect();
-            seq.serialize_element(&obj)?;
+            csv_writer.write_record(&fields)?;
+        }
+        csv_writer.flush()?;
+        Ok(())
+    }
+}
+
+/// A row of log data
+#[derive(Debug, Clone)]
+pub struct Row {
+    /// Timestamp of the row, the first date time value
+    pub datetime: Option<NaiveDateTime>,
+    /// Values of all columns, including the timestamp
+    pub values: Vec<CellValue>,
+}
+
+impl Row {
+    pub(crate) fn new(values: Vec<CellValue>) -> Self {
+        let datetime = values.iter().find_map(|v| match v {
+            CellValue::DateTime(dt) => Some(*dt),
+            _ => None,
+        });
+        Self { datetime, values }
+    }
+}
+
+/// Output format of timestamps in JSON
+#[derive(Debug, Default, Clone, Copy)]
+pub enum TimestampFormat {
+    /// Seconds since Unix epoch
+    Epoch,
+    /// ISO 8601 string, e.g. `2025-01-25T17:15:06`
+    #[default]
+    Iso8601,
+}
+
+/// Options of table to JSON conversion
+#[derive(Debug, Default, Clone)]
+pub struct JsonOptions {
+    pub timestamp_format: TimestampFormat,
+    /// Time zone the timestamps are converted into, defaults to the time zone of the table.
+    /// ISO 8601 strings carry the offset when it's known, and timestamps of a table of an
+    /// unknown time zone are taken as UTC when converted.
+    pub tz: Option<FixedOffset>,
+}
+
+/// Strategy of filling null readings, e.g. of sensor dropouts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillStrategy {
+    /// Carry the last reading forward
+    Forward,
+    /// Carry the next reading backward
+    Backward,
+    /// Fill with a constant value
+    Value(f64),
+}
+
+/// Cleanup steps applied by `AquaTrollLogData::normalize_with`, all enabled by default
+#[derive(Debug, Clone)]
+pub struct NormalizeOptions {
+    /// Sort rows by timestamp
+    pub sort_by_time: bool,
+    /// Keep only the first row of each timestamp
+    pub dedup_timestamps: bool,
+    /// Drop columns without any value
+    pub drop_null_columns: bool,
+    /// Trim column names and collapse inner whitespace
+    pub normalize_column_names: bool,
+    /// Record a summary of the log data in the `Normalized` attribute
+    pub attach_metadata: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            sort_by_time: true,
+            dedup_timestamps: true,
+            drop_null_columns: true,
+            normalize_column_names: true,
+            attach_metadata: true,
+        }
+    }
+}
+
+/// Convert a timestamp of the `source` time zone into JSON, in the `tz` time zone of `options`
+///
+/// Timestamps of an unknown `source` time zone are taken as UTC when converted.
+fn datetime_to_json(
+    dt: &NaiveDateTime,
+    source: Option<FixedOffset>,
+    options: &JsonOptions,
+) -> Value {
+    let dt = match (source, options.tz) {
+        (None, None) => {
+            return match options.timestamp_format {
+                TimestampFormat::Epoch => Value::Number(dt.and_utc().timestamp().into()),
+                TimestampFormat::Iso8601 => {
+                    Value::String(dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+                }
+            };
+        }
+        (Some(source), None) => dt.and_local_timezone(source).single(),
+        (None, Some(tz)) => Some(dt.and_utc().with_timezone(&tz)),
+        (Some(source), Some(tz)) => dt
+            .and_local_timezone(source)
+            .single()
+            .map(|dt| dt.with_timezone(&tz)),
+    };
+    match (options.timestamp_format, dt) {
+        (_, None) => Value::Null,
+        (TimestampFormat::Epoch, Some(dt)) => Value::Number(dt.timestamp().into()),
+        (TimestampFormat::Iso8601, Some(dt)) => {
+            Value::String(dt.format("%Y-%m-%dT%H:%M:%S%.f%:z").to_string())
+        }
+    }
+}
+
+/// Convert a table into an array of JSON objects keyed by column names
+pub fn table_to_json(table: &Table, options: &JsonOptions) -> Value {
+    Value::Array(
+        table
+            .json_rows(options)
+            .map(|row| Value::Object(table.columns.iter().cloned().zip(row).collect()))
+            .collect(),
+    )
+}
+
+/// Parse an ISO 8601 timestamp of `datetime_to_json`, with the offset if it carries one
+fn datetime_from_json(value: &str) -> Option<(NaiveDateTime, Option<FixedOffset>)> {
+    match DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%:z") {
+        Ok(dt) => Some((dt.naive_local(), Some(*dt.offset()))),
+        Err(_) => NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+            .ok()
+            .map(|dt| (dt, None)),
+    }
+}
+
+/// Type of a column of JSON values, timestamps of ISO 8601 strings
+fn json_column_type<'a>(values: impl Iterator<Item = &'a Value>) -> ColumnType {
+    let values: Vec<_> = values.filter(|v| !v.is_null()).collect();
+    match values.first() {
+        Some(Value::String(_))
+            if values
+                .iter()
+                .all(|v| v.as_str().and_then(datetime_from_json).is_some()) =>
+        {
+            ColumnType::DateTime
         }
-        seq.end()
+        Some(Value::String(_)) => ColumnType::Text,
+        Some(Value::Bool(_)) => ColumnType::Boolean,
+        Some(Value::Number(_)) if values.iter().all(|v| v.is_i64()) => ColumnType::Int64,
+        _ => ColumnType::Float64,
     }
 }
 
-#[derive(Clone, Copy)]
-enum ColumnType {
+/// Convert an array of JSON objects of `table_to_json` back into a table
+///
+/// Columns are the keys of the objects in order, missing keys are null cells. Column types
+/// are inferred from the values: ISO 8601 strings as timestamps, with the time zone taken
+/// from their offset, integers as `Int64` and other numbers as `Float64`. Column metadata
+/// isn't part of the JSON and is left empty.
+pub fn table_from_json(value: &Value) -> Result<Table, AquaTrollLogError> {
+    let objects = value
+        .as_array()
+        .ok_or(AquaTrollLogError::InvalidData)?
+        .iter()
+        .map(|row| row.as_object().ok_or(AquaTrollLogError::InvalidData))
+        .collect::<Result<Vec<&Map<String, Value>>, _>>()?;
+
+    let mut columns: Vec<String> = vec![];
+    for key in objects.iter().flat_map(|object| object.keys()) {
+        if !columns.contains(key) {
+            columns.push(key.clone());
+        }
+    }
+    let column_types: Vec<_> = columns
+        .iter()
+        .map(|name| json_column_type(objects.iter().filter_map(|object| object.get(name))))
+        .collect();
+
+    let mut timezone = None;
+    let mut rows = Vec::with_capacity(objects.len());
+    for object in objects {
+        let mut row = Vec::with_capacity(columns.len());
+        for (name, col_type) in columns.iter().zip(&column_types) {
+            let cell = match (object.get(name).unwrap_or(&Value::Null), col_type) {
+                (Value::Null, _) => CellValue::Null,
+                (Value::String(s), ColumnType::DateTime) => {
+                    let (dt, offset) =
+                        datetime_from_json(s).ok_or(AquaTrollLogError::InvalidData)?;
+                    timezone = timezone.or(offset);
+                    CellValue::DateTime(dt)
+                }
+                (Value::String(s), ColumnType::Text) => CellValue::Text(s.clone()),
+                (Value::Bool(b), ColumnType::Boolean) => CellValue::Boolean(*b),
+                (Value::Number(n), ColumnType::Int64) => {
+                    CellValue::Int64(n.as_i64().ok_or(AquaTrollLogError::InvalidData)?)
+                }
+                (Value::Number(n), ColumnType::Float64) => {
+                    CellValue::Float64(n.as_f64().ok_or(AquaTrollLogError::InvalidData)?)
+                }
+                _ => return Err(AquaTrollLogError::InvalidData),
+            };
+            row.push(cell);
+        }
+        rows.push(row);
+    }
+
+    Ok(Table {
+        column_metadata: vec![HashMap::new(); columns.len()],
+        columns,
+        rows,
+        timezone,
+    })
+}
+
+fn cell_to_json(cell: &CellValue, source: Option<FixedOffset>, options: &JsonOptions) -> Value {
+    match cell {
+        CellValue::DateTime(dt) => datetime_to_json(dt, source, options),
+        CellValue::Float64(f) => serde_json::Number::from_f64(*f)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        CellValue::Int64(v) => Value::from(*v),
+        CellValue::Boolean(b) => Value::Bool(*b),
+        CellValue::Text(s) => Value::String(s.clone()),
+        CellValue::Null => Value::Null,
+    }
+}
+
+impl Table {
+    /// Rows as JSON values in column order, converted as by `table_to_json`
+    pub fn json_rows(&self, options: &JsonOptions) -> impl Iterator<Item = Vec<Value>> + '_ {
+        let options = options.clone();
+        self.rows.iter().map(move |row| {
+            row.iter()
+                .map(|cell| cell_to_json(cell, self.timezone, &options))
+                .collect()
+        })
+    }
+}
+
+impl Serialize for Table {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        table_to_json(self, &JsonOptions::default()).serialize(serializer)
+    }
+}
+
+/// Check if a reading is blank or a placeholder like `---` of a missing value
+fn is_missing_value(value: &str) -> bool {
+    value.trim().chars().all(|c| c == '-')
+}
+
+/// Parse a numeric reading, accepting the infinity spellings of sensor faults
+///
+/// Besides the spellings of `f64::from_str` (`inf`, `Infinity`, overflows like `1e999`), the
+/// MSVC style `1.#INF` and the `∞` sign are read as infinity.
+pub(crate) fn parse_reading(value: &str) -> Result<f64, AquaTrollLogError> {
+    let value = value.trim();
+    value.parse().or_else(|e| {
+        let (sign, magnitude) = match value.strip_prefix('-') {
+            Some(magnitude) => (-1.0, magnitude),
+            None => (1.0, value.strip_prefix('+').unwrap_or(value)),
+        };
+        match magnitude.to_ascii_uppercase().as_str() {
+            "1.#INF" | "∞" => Ok(sign * f64::INFINITY),
+            _ => Err(AquaTrollLogError::from(e)),
+        }
+    })
+}
+
+/// Parse a flag of a `Marked` column, unknown values are an error
+fn parse_flag(value: &str) -> Result<bool, AquaTrollLogError> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "marked" | "true" | "1" => Ok(true),
+        "" | "unmarked" | "false" | "0" => Ok(false),
+        _ => Err(AquaTrollLogError::InvalidBoolean(value.to_string())),
+    }
+}
+
+/// Type of the cells of a table column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
     DateTime,
     Text,
+    Boolean,
     Float64,
+    /// Integers, e.g. counts, only selected by `field_types`
+    Int64,
 }
 
-pub(crate) struct TableBuilder {
+/// Type of columns named other than a parameter
+const COLUMN_TYPES: &[(&str, ColumnType)] =
+    &[("Note", ColumnType::Text), ("Marked", ColumnType::Boolean)];
+
+/// Builder of tables from rows of text values, as of the log readers
+///
+/// Column types are inferred from the field names: a date time header (e.g. `Date and Time`)
+/// is parsed as the `DateTime` column, `Note` and `Marked` as text and flags, other columns as
+/// readings. Tables built so convert to JSON, CSV and Arrow alike of tables of logs.
+///
+/// `new`, `field_names`, `with_datetime_parser`, `try_push_row` and `try_build` are the
+/// public API, other methods are internal to the log readers.
+///
+/// ```
+/// use aqua_troll_log_reader::{CellValue, TableBuilder};
+///
+/// let table = TableBuilder::new()
+///     .field_names(vec!["Date and Time".to_string(), "pH (pH)".to_string()])
+///     .try_push_row(vec!["2021/7/20 PM 12:00:00".to_string(), "7.1".to_string()])
+///     .unwrap()
+///     .try_build()
+///     .unwrap();
+/// assert_eq!(table.columns, vec!["DateTime", "pH (pH)"]);
+/// assert!(matches!(table.rows[0][1], CellValue::Float64(v) if v == 7.1));
+/// ```
+pub struct TableBuilder {
     column_types: Vec<ColumnType>,
     columns: Vec<String>,
     rows: Vec<Vec<CellValue>>,
     datetime_parser: DateTimeParser,
+    timezone: Option<FixedOffset>,
+    column_metadata: Vec<HashMap<String, String>>,
+    infinite_as_null: bool,
+    datetime_column_name: Option<String>,
+    lenient_header_match: bool,
+    decimal_comma: bool,
+    /// Offset of the first time zone abbreviation of timestamps, the time zone of the table
+    /// if not set
+    abbreviation_offset: Cell<Option<FixedOffset>>,
+}
+
+impl Default for TableBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TableBuilder {
@@ -160,6 +1117,13 @@ impl TableBuilder {
             columns: Vec::new(),
             rows: Vec::new(),
             datetime_parser: DateTimeParser::Default,
+            timezone: None,
+            column_metadata: Vec::new(),
+            infinite_as_null: false,
+            datetime_column_name: None,
+            lenient_header_match: false,
+            decimal_comma: false,
+            abbreviation_offset: Cell::new(None),
         }
     }
 
@@ -168,12 +1132,15 @@ impl TableBuilder {
         let mut column_types = Vec::new();
 
         for name in field_names {
-            if ["Date and Time", "Date Time", "Date/Time", "DateTime"].contains(&name.as_str()) {
-                columns.push("DateTime".to_string());
+            if is_datetime_header_with(&name, self.lenient_header_match) {
+                columns.push(DATETIME_COLUMN.to_string());
                 column_types.push(ColumnType::DateTime);
-            } else if name == "Note" || name == "Marked" {
+            } else if let Some((_, col_type)) = COLUMN_TYPES
+                .iter()
+                .find(|(n, _)| header_matches(&name, n, self.lenient_header_match))
+            {
                 columns.push(name);
-                column_types.push(ColumnType::Text);
+                column_types.push(*col_type);
             } else {
                 columns.push(name);
                 column_types.push(ColumnType::Float64);
@@ -185,23 +1152,184 @@ impl TableBuilder {
         self
     }
 
+    /// Set types of the columns with the given names, overriding the types inferred from the
+    /// names, must be set after field names
+    pub(crate) fn field_types(mut self, overrides: &HashMap<String, ColumnType>) -> Self {
+        for (name, col_type) in self.columns.iter().zip(self.column_types.iter_mut()) {
+            if let Some(override_type) = overrides.get(name) {
+                *col_type = *override_type;
+            }
+        }
+        self
+    }
+
+    /// Match field names against the known header names ignoring whitespace and case, must
+    /// be set before field names
+    pub(crate) fn with_lenient_header_match(mut self, lenient: bool) -> Self {
+        self.lenient_header_match = lenient;
+        self
+    }
+
+    /// Read readings of comma decimals, e.g. `21,6019`
+    pub(crate) fn with_decimal_comma(mut self, decimal_comma: bool) -> Self {
+        self.decimal_comma = decimal_comma;
+        self
+    }
+
     pub fn with_datetime_parser(mut self, parser: DateTimeParser) -> Self {
         self.datetime_parser = parser;
         self
     }
 
-    pub fn try_push_row(mut self, row_values: Vec<String>) -> Result<Self, AquaTrollLogError> {
+    /// Set metadata of columns, in the order of field names
+    pub(crate) fn with_column_metadata(
+        mut self,
+        column_metadata: Vec<HashMap<String, String>>,
+    ) -> Self {
+        self.column_metadata = column_metadata;
+        self
+    }
+
+    /// Read infinite readings as null
+    pub(crate) fn with_infinite_as_null(mut self, infinite_as_null: bool) -> Self {
+        self.infinite_as_null = infinite_as_null;
+        self
+    }
+
+    pub(crate) fn with_timezone(mut self, timezone: Option<FixedOffset>) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Name the timestamp column of the built table, `DateTime` if not set
+    pub(crate) fn with_datetime_column_name(mut self, name: Option<String>) -> Self {
+        self.datetime_column_name = name;
+        self
+    }
+
+    pub fn try_push_row(self, row_values: Vec<String>) -> Result<Self, AquaTrollLogError> {
+        self.try_push_nullable_row(row_values.into_iter().map(Some).collect())
+    }
+
+    pub(crate) fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub(crate) fn timezone(&self) -> Option<FixedOffset> {
+        self.timezone
+    }
+
+    pub(crate) fn column_metadata(&self) -> &[HashMap<String, String>] {
+        &self.column_metadata
+    }
+
+    /// Parse a row of values into cells of the column types
+    pub(crate) fn parse_row(
+        &self,
+        row_values: Vec<String>,
+    ) -> Result<Vec<CellValue>, AquaTrollLogError> {
+        self.parse_nullable_row(row_values.into_iter().map(Some).collect())
+    }
+
+    fn parse_nullable_row(
+        &self,
+        row_values: Vec<Option<String>>,
+    ) -> Result<Vec<CellValue>, AquaTrollLogError> {
+        self.check_row_len(row_values.len())?;
         let mut row = Vec::with_capacity(row_values.len());
-        for (value_str, col_type) in row_values.into_iter().zip(&self.column_types) {
+        for ((value_str, col_type), name) in row_values
+            .into_iter()
+            .zip(&self.column_types)
+            .zip(&self.columns)
+        {
+            let Some(value_str) = value_str else {
+                row.push(CellValue::Null);
+                continue;
+            };
             let cell = match col_type {
-                ColumnType::DateTime => {
-                    CellValue::DateTime(self.datetime_parser.parse(&value_str)?)
-                }
+                ColumnType::DateTime => CellValue::DateTime(self.parse_datetime(&value_str)?),
                 ColumnType::Text => CellValue::Text(value_str),
-                ColumnType::Float64 => CellValue::Float64(value_str.parse()?),
+                ColumnType::Boolean => CellValue::Boolean(parse_flag(&value_str)?),
+                ColumnType::Int64 if is_missing_value(&value_str) => CellValue::Null,
+                ColumnType::Int64 => CellValue::Int64(value_str.trim().parse().map_err(|_| {
+                    AquaTrollLogError::InvalidInteger {
+                        column: name.clone(),
+                        value: value_str.clone(),
+                    }
+                })?),
+                ColumnType::Float64 if is_missing_value(&value_str) => CellValue::Null,
+                ColumnType::Float64 if self.decimal_comma => {
+                    match parse_reading(&value_str.replace(',', "."))? {
+                        v if v.is_infinite() && self.infinite_as_null => CellValue::Null,
+                        v => CellValue::Float64(v),
+                    }
+                }
+                ColumnType::Float64 => match parse_reading(&value_str)? {
+                    v if v.is_infinite() && self.infinite_as_null => CellValue::Null,
+                    v => CellValue::Float64(v),
+                },
             };
             row.push(cell);
         }
+        Ok(row)
+    }
+
+    /// Parse a timestamp with the date time parser of the table
+    ///
+    /// Timestamps of a trailing time zone abbreviation (e.g. `JST`) are converted into the
+    /// time zone of the table if it's set, otherwise the first one sets the time zone of the
+    /// table, and timestamps of other abbreviations (e.g. `EDT` after `EST`) are converted
+    /// into it.
+    pub(crate) fn parse_datetime(&self, value: &str) -> Result<NaiveDateTime, AquaTrollLogError> {
+        let error = match self.datetime_parser.parse(value) {
+            Ok(dt) => return Ok(dt),
+            Err(error) => error,
+        };
+        let Some((value, offset)) = split_time_zone_abbreviation(value) else {
+            return Err(error);
+        };
+        let dt = self.datetime_parser.parse(value)?;
+        let timezone = match self.timezone {
+            Some(timezone) => timezone,
+            None => {
+                let timezone = self.abbreviation_offset.get().unwrap_or(offset);
+                self.abbreviation_offset.set(Some(timezone));
+                timezone
+            }
+        };
+        let shift = timezone.local_minus_utc() - offset.local_minus_utc();
+        Ok(dt + chrono::Duration::seconds(shift.into()))
+    }
+
+    /// Fail on a row of other than one value of each column, the line number is the number of
+    /// the row in the table
+    fn check_row_len(&self, found: usize) -> Result<(), AquaTrollLogError> {
+        if found != self.columns.len() {
+            return Err(AquaTrollLogError::ColumnCountMismatch {
+                line_number: self.rows.len() + 1,
+                expected: self.columns.len(),
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    /// Push a row of cells already parsed, e.g. by `parse_row`
+    pub(crate) fn push_parsed_row(
+        mut self,
+        row: Vec<CellValue>,
+    ) -> Result<Self, AquaTrollLogError> {
+        self.check_row_len(row.len())?;
+        self.rows.push(row);
+        Ok(self)
+    }
+
+    /// Push a row of values with `None` for null cells
+    pub(crate) fn try_push_nullable_row(
+        mut self,
+        row_values: Vec<Option<String>>,
+    ) -> Result<Self, AquaTrollLogError> {
+        let row = self.parse_nullable_row(row_values)?;
         self.rows.push(row);
         Ok(self)
     }
@@ -210,9 +1338,23 @@ impl TableBuilder {
         if self.columns.is_empty() {
             return Err(AquaTrollLogError::InvalidData);
         }
+        let mut column_metadata = self.column_metadata;
+        column_metadata.resize_with(self.columns.len(), HashMap::new);
+
+        let mut columns = self.columns;
+        if let Some(name) = self.datetime_column_name {
+            for (column, col_type) in columns.iter_mut().zip(&self.column_types) {
+                if *col_type == ColumnType::DateTime {
+                    *column = name.clone();
+                }
+            }
+        }
+
         Ok(Table {
-            columns: self.columns,
+            columns,
             rows: self.rows,
+            timezone: self.timezone.or(self.abbreviation_offset.get()),
+            column_metadata,
         })
     }
 }
@@ -231,6 +1373,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn datetime_str_am_pm_position() {
+        let datetime = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            am_pm_position("2021/7/20 PM 12:00:00"),
+            Some(AmPmPosition::BeforeTime)
+        );
+        assert_eq!(
+            am_pm_position("2025/1/26 05:15:06 pm"),
+            Some(AmPmPosition::AfterTime)
+        );
+        assert_eq!(am_pm_position("2025-01-25 16:24:59"), None);
+
+        assert_eq!(
+            parse_datetime_str("2021/7/20 AM 12:00:00").unwrap(),
+            datetime("2021-07-20 00:00:00")
+        );
+        assert_eq!(
+            parse_datetime_str("2025/1/26 05:15:06 PM").unwrap(),
+            datetime("2025-01-26 17:15:06")
+        );
+        assert_eq!(
+            parse_datetime_str("2025-01-26 PM 05:15:06").unwrap(),
+            datetime("2025-01-26 17:15:06")
+        );
+        assert_eq!(
+            parse_datetime_str("2025-01-26 05:15:06 AM").unwrap(),
+            datetime("2025-01-26 05:15:06")
+        );
+        assert_eq!(
+            parse_datetime_str("2025/1/26 17:15:06").unwrap(),
+            datetime("2025-01-26 17:15:06")
+        );
+        // 24-hour times of a marker
+        assert!(parse_datetime_str("2025/1/26 17:15:06 PM").is_err());
+    }
+
+    #[test]
+    fn datetime_str_chinese_am_pm() {
+        let expected =
+            NaiveDateTime::parse_from_str("2025-01-30 17:00:59", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            parse_datetime_str("2025/1/30 下午 05:00:59").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_datetime_str("2025/1/30 下午　05:00:59").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_datetime_str("2025/1/30 下午05:00:59").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_datetime_str("2025/1/30 05:00:59 下午").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_datetime_str("2025/1/30 上午 05:00:59").unwrap(),
+            expected - chrono::Duration::hours(12)
+        );
+        assert!(parse_datetime_str("2025/1/30 晚上 05:00:59").is_err());
+    }
+
     #[test]
     fn parse_with_custom_format() {
         let result =
@@ -251,6 +1457,124 @@ mod tests {
         );
     }
 
+    #[test]
+    fn time_zone() {
+        let tz = |secs| FixedOffset::east_opt(secs);
+        assert_eq!(parse_time_zone("台北標準時間"), tz(8 * 3600));
+        assert_eq!(parse_time_zone("Tokyo Standard Time"), tz(9 * 3600));
+        assert_eq!(parse_time_zone("UTC+05:30"), tz(5 * 3600 + 1800));
+        assert_eq!(parse_time_zone("-05:00"), tz(-5 * 3600));
+        assert_eq!(parse_time_zone("08:00:00"), tz(8 * 3600));
+        assert_eq!(parse_time_zone("UTC"), tz(0));
+        assert_eq!(parse_time_zone("JST"), tz(9 * 3600));
+        for ambiguous in ["CST", "IST", "CDT", "MST", "PST"] {
+            assert_eq!(parse_time_zone(ambiguous), None, "{ambiguous}");
+        }
+        assert_eq!(parse_time_zone("Unknown"), None);
+    }
+
+    #[test]
+    fn datetime_time_zone_abbreviation() {
+        let builder =
+            || TableBuilder::new().field_names(vec!["Date and Time".to_string(), "pH".to_string()]);
+        let datetime = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let table = builder()
+            .try_push_row(vec!["2024-10-09 16:29:44 HKT".to_string(), "7".to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert!(
+            matches!(table.rows[0][0], CellValue::DateTime(dt) if dt == datetime("2024-10-09 16:29:44"))
+        );
+        assert_eq!(table.timezone, FixedOffset::east_opt(8 * 3600));
+
+        // Converted into the time zone of the table, or into the first one otherwise
+        let utc = FixedOffset::east_opt(0);
+        let table = builder()
+            .with_timezone(utc)
+            .try_push_row(vec!["2024-10-09 16:29:44 HKT".to_string(), "7".to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert!(
+            matches!(table.rows[0][0], CellValue::DateTime(dt) if dt == datetime("2024-10-09 08:29:44"))
+        );
+        assert_eq!(table.timezone, utc);
+
+        // Ambiguous abbreviations are rejected
+        for ambiguous in ["CST", "CDT", "MST"] {
+            assert!(builder()
+                .try_push_row(vec![
+                    format!("2024-10-09 16:29:44 {ambiguous}"),
+                    "7".to_string()
+                ])
+                .is_err());
+        }
+
+        let table = builder()
+            .try_push_row(vec!["2024-11-03 00:30:00 EDT".to_string(), "7".to_string()])
+            .unwrap()
+            .try_push_row(vec!["2024-11-03 01:30:00 EST".to_string(), "7".to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(table.timezone, FixedOffset::east_opt(-4 * 3600));
+        assert!(
+            matches!(table.rows[1][0], CellValue::DateTime(dt) if dt == datetime("2024-11-03 02:30:00"))
+        );
+
+        assert!(builder()
+            .try_push_row(vec!["2024-10-09 16:29:44 XYZ".to_string(), "7".to_string()])
+            .is_err());
+    }
+
+    #[test]
+    fn sample_rate() {
+        let secs = |secs| Some(std::time::Duration::from_secs(secs));
+        assert_eq!(
+            parse_sample_rate("Days: 0 hrs: 00 mins: 00 secs: 15"),
+            secs(15)
+        );
+        assert_eq!(
+            parse_sample_rate("Days: 1 hrs: 02 mins: 30 secs: 00"),
+            secs(86400 + 2 * 3600 + 30 * 60)
+        );
+        assert_eq!(parse_sample_rate("Days: 0 hrs: 00 mins: xx secs: 15"), None);
+        assert_eq!(parse_sample_rate("Days: 0 hrs:"), None);
+        assert_eq!(parse_sample_rate("Weeks: 1"), None);
+        assert_eq!(parse_sample_rate(""), None);
+    }
+
+    #[test]
+    fn typed_attr() {
+        let tz = FixedOffset::east_opt(8 * 3600);
+        assert_eq!(typed_attr_value("999996", None), 999996);
+        assert_eq!(typed_attr_value("0.65", None), 0.65);
+        assert_eq!(typed_attr_value("-15.178", None), -15.178);
+        assert_eq!(typed_attr_value("0012", None), "0012");
+        assert_eq!(typed_attr_value("Disabled", None), false);
+        assert_eq!(typed_attr_value("true", None), true);
+        assert_eq!(typed_attr_value("inf", None), "inf");
+        assert_eq!(typed_attr_value("0 (pH)", None), "0 (pH)");
+        assert_eq!(
+            typed_attr_value("2025/1/25 PM 04:23:18 台北標準時間", None),
+            "2025-01-25T16:23:18+08:00"
+        );
+        assert_eq!(
+            typed_attr_value("2024-10-09 16:29:44", None),
+            "2024-10-09T16:29:44"
+        );
+        assert_eq!(
+            typed_attr_value("2024-10-09 16:29:44", tz),
+            "2024-10-09T16:29:44+08:00"
+        );
+        assert_eq!(typed_attr_value("No Stop Time", tz), "No Stop Time");
+
+        let options = ReadOptions::default();
+        assert_eq!(options.attr_value("999996"), "999996");
+    }
+
     #[test]
     fn table_builder_with_datetime_format() {
         let field_names = vec!["Date Time".to_string(), "Value".to_string()];
@@ -349,4 +1673,489 @@ mod tests {
         assert!(matches!(&table.rows[0][3], CellValue::Float64(v) if *v == 1.0));
         assert!(matches!(&table.rows[1][3], CellValue::Float64(v) if *v == 2.0));
     }
+
+    #[test]
+    fn table_builder_column_count() {
+        let table_builder =
+            || TableBuilder::new().field_names(vec!["Date and Time".to_string(), "pH".to_string()]);
+        assert!(matches!(
+            table_builder().try_push_row(vec!["2021/7/20 PM 12:00:00".to_string()]),
+            Err(AquaTrollLogError::ColumnCountMismatch {
+                line_number: 1,
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            table_builder().push_parsed_row(vec![CellValue::Null; 3]),
+            Err(AquaTrollLogError::ColumnCountMismatch { found: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn table_builder_marked() {
+        let field_names = vec!["Date and Time".to_string(), "Marked".to_string()];
+        let table = TableBuilder::new()
+            .field_names(field_names.clone())
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:00".to_string(),
+                "Unmarked".to_string(),
+            ])
+            .unwrap()
+            .try_push_row(vec![
+                "2021/7/20 PM 12:01:00".to_string(),
+                "Marked".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert!(matches!(table.rows[0][1], CellValue::Boolean(false)));
+        assert!(matches!(table.rows[1][1], CellValue::Boolean(true)));
+
+        let result = TableBuilder::new()
+            .field_names(field_names)
+            .try_push_row(vec!["2021/7/20 PM 12:00:00".to_string(), "Yes".to_string()]);
+        assert!(matches!(result, Err(AquaTrollLogError::InvalidBoolean(s)) if s == "Yes"));
+    }
+
+    #[test]
+    fn table_iter_columns() {
+        let table = TableBuilder::new()
+            .field_names(vec!["Date and Time".to_string(), "Value".to_string()])
+            .try_push_row(vec!["2021/7/20 PM 12:00:00".to_string(), "1.0".to_string()])
+            .unwrap()
+            .try_push_row(vec!["2021/7/20 PM 12:01:00".to_string(), "2.0".to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let columns: Vec<_> = table.iter_columns().collect();
+        assert_eq!(columns.len(), table.num_columns());
+        for (i, (name, values)) in columns.iter().enumerate() {
+            assert_eq!(*name, table.column_name(i));
+            assert_eq!(values.len(), table.num_rows());
+        }
+        assert!(matches!(columns[1].1[1], CellValue::Float64(v) if *v == 2.0));
+    }
+
+    #[test]
+    fn table_cleanup() {
+        let mut table = TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                " Value  (m) ".to_string(),
+                "Empty".to_string(),
+            ])
+            .try_push_row(vec![
+                "2021/7/20 PM 12:01:00".to_string(),
+                "2.0".to_string(),
+                "".to_string(),
+            ])
+            .unwrap()
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:00".to_string(),
+                "1.0".to_string(),
+                "".to_string(),
+            ])
+            .unwrap()
+            .try_push_row(vec![
+                "2021/7/20 PM 12:01:00".to_string(),
+                "3.0".to_string(),
+                "".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        table.sort_by_time();
+        assert!(matches!(table.rows[0][1], CellValue::Float64(v) if v == 1.0));
+        assert!(matches!(table.rows[1][1], CellValue::Float64(v) if v == 2.0));
+
+        assert_eq!(table.dedup_timestamps(), 1);
+        assert_eq!(table.num_rows(), 2);
+        assert!(matches!(table.rows[1][1], CellValue::Float64(v) if v == 2.0));
+
+        assert_eq!(table.drop_null_columns(), vec!["Empty".to_string()]);
+        assert_eq!(table.num_columns(), 2);
+        assert!(table.rows.iter().all(|row| row.len() == 2));
+
+        table.normalize_column_names();
+        assert_eq!(table.column_name(1), "Value (m)");
+    }
+
+    #[test]
+    fn table_downcast_integer_columns() {
+        let mut table = TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                "Count".to_string(),
+                "Value".to_string(),
+            ])
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:00".to_string(),
+                "3".to_string(),
+                "1.5".to_string(),
+            ])
+            .unwrap()
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:30".to_string(),
+                "".to_string(),
+                "2".to_string(),
+            ])
+            .unwrap()
+            .try_push_row(vec![
+                "2021/7/20 PM 12:01:00".to_string(),
+                "-4.0".to_string(),
+                "1e300".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        assert_eq!(table.downcast_integer_columns(), vec!["Count".to_string()]);
+        assert!(matches!(table.rows[0][1], CellValue::Int64(3)));
+        assert!(matches!(table.rows[1][1], CellValue::Null));
+        assert!(matches!(table.rows[2][1], CellValue::Int64(-4)));
+        assert!(matches!(table.rows[1][2], CellValue::Float64(v) if v == 2.0));
+    }
+
+    #[test]
+    fn table_short_rows() {
+        let mut table = TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                "Count".to_string(),
+                "Value".to_string(),
+            ])
+            .try_push_row(vec![
+                "2021/7/20 PM 12:01:00".to_string(),
+                "3".to_string(),
+                "1.5".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+        // Rows of tables built by hand may be short
+        table.rows.insert(
+            0,
+            vec![CellValue::DateTime(
+                NaiveDateTime::parse_from_str("2021-07-20 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+            )],
+        );
+
+        assert!(matches!(
+            table.column_values(2).next(),
+            Some(CellValue::Null)
+        ));
+        assert_eq!(table.non_null_columns(), vec![true, true, true]);
+        assert_eq!(table.datetime_column(), Some(0));
+        assert_eq!(table.timestamps().len(), 2);
+        table.sort_by_time();
+        table.fill_missing(FillStrategy::Value(0.0));
+        assert_eq!(table.downcast_integer_columns(), vec!["Count".to_string()]);
+        assert_eq!(table.rows[0].len(), 1);
+        assert!(matches!(table.rows[1][1], CellValue::Int64(3)));
+    }
+
+    #[test]
+    fn infinite_readings() {
+        for value in ["Inf", "Infinity", "1e999", "+INF", "1.#INF", "∞"] {
+            assert_eq!(parse_reading(value).unwrap(), f64::INFINITY, "{value}");
+        }
+        for value in ["-Inf", "-Infinity", "-1e999", "-1.#INF", "-∞"] {
+            assert_eq!(parse_reading(value).unwrap(), f64::NEG_INFINITY, "{value}");
+        }
+        assert_eq!(parse_reading(" 7.034 ").unwrap(), 7.034);
+        assert!(parse_reading("1.#INF0").is_err());
+
+        let field_names = vec!["Date and Time".to_string(), "Value".to_string()];
+        let row = vec!["2021/7/20 PM 12:00:00".to_string(), "Infinity".to_string()];
+        let table = TableBuilder::new()
+            .field_names(field_names.clone())
+            .try_push_row(row.clone())
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert!(matches!(table.rows[0][1], CellValue::Float64(v) if v == f64::INFINITY));
+
+        let table = TableBuilder::new()
+            .field_names(field_names)
+            .with_infinite_as_null(true)
+            .try_push_row(row)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert!(matches!(table.rows[0][1], CellValue::Null));
+    }
+
+    #[test]
+    fn table_write_csv_in_timezone() {
+        let mut table = TableBuilder::new()
+            .field_names(vec!["Date and Time".to_string(), "Value".to_string()])
+            .try_push_row(vec!["2021/7/20 PM 12:00:00".to_string(), "1.5".to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let mut buf = vec![];
+        table
+            .write_csv_in_timezone(
+                &mut buf,
+                &FixedOffset::east_opt(8 * 3600).unwrap(),
+                "%Y-%m-%d %H:%M:%S",
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "DateTime,Value\n2021-07-20 20:00:00,1.5\n"
+        );
+
+        table.timezone = FixedOffset::east_opt(8 * 3600);
+        let mut buf = vec![];
+        table
+            .write_csv_in_timezone(&mut buf, &Utc, "%d/%m/%Y %H:%M %Z")
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "DateTime,Value\n20/07/2021 04:00 UTC,1.5\n"
+        );
+    }
+
+    #[test]
+    fn table_fill_missing() {
+        let table = TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                "Note".to_string(),
+                "Value".to_string(),
+            ])
+            .try_push_nullable_row(vec![Some("2021/7/20 PM 12:00:00".to_string()), None, None])
+            .unwrap()
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:30".to_string(),
+                "".to_string(),
+                "1.0".to_string(),
+            ])
+            .unwrap()
+            .try_push_nullable_row(vec![Some("2021/7/20 PM 12:01:00".to_string()), None, None])
+            .unwrap()
+            .try_push_row(vec![
+                "2021/7/20 PM 12:01:30".to_string(),
+                "".to_string(),
+                "2.0".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+        let values = |table: &Table| -> Vec<Option<f64>> {
+            table
+                .column_values(2)
+                .map(|v| match v {
+                    CellValue::Float64(v) => Some(*v),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let mut forward = table.clone();
+        forward.fill_missing(FillStrategy::Forward);
+        assert_eq!(
+            values(&forward),
+            vec![None, Some(1.0), Some(1.0), Some(2.0)]
+        );
+        assert!(matches!(forward.rows[0][1], CellValue::Null));
+
+        let mut backward = table.clone();
+        backward.fill_missing(FillStrategy::Backward);
+        assert_eq!(
+            values(&backward),
+            vec![Some(1.0), Some(1.0), Some(2.0), Some(2.0)]
+        );
+
+        let mut constant = table.clone();
+        constant.fill_missing(FillStrategy::Value(0.0));
+        assert_eq!(
+            values(&constant),
+            vec![Some(0.0), Some(1.0), Some(0.0), Some(2.0)]
+        );
+        assert!(matches!(constant.rows[2][1], CellValue::Null));
+    }
+
+    #[test]
+    fn table_builder_field_types() {
+        let overrides = [
+            ("Site ID".to_string(), ColumnType::Text),
+            ("Marked".to_string(), ColumnType::Text),
+            ("Flag".to_string(), ColumnType::Boolean),
+        ]
+        .into();
+        let table = TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                "Site ID".to_string(),
+                "Marked".to_string(),
+                "Flag".to_string(),
+                "Value".to_string(),
+            ])
+            .field_types(&overrides)
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:00".to_string(),
+                "TW-01".to_string(),
+                "Yes".to_string(),
+                "1".to_string(),
+                "1.0".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        assert!(matches!(&table.rows[0][1], CellValue::Text(s) if s == "TW-01"));
+        assert!(matches!(&table.rows[0][2], CellValue::Text(s) if s == "Yes"));
+        assert!(matches!(table.rows[0][3], CellValue::Boolean(true)));
+        assert!(matches!(table.rows[0][4], CellValue::Float64(v) if v == 1.0));
+    }
+
+    #[test]
+    fn table_builder_int64() {
+        let field_names = vec!["Date and Time".to_string(), "Record Count".to_string()];
+        let overrides = [("Record Count".to_string(), ColumnType::Int64)].into();
+        let table = TableBuilder::new()
+            .field_names(field_names.clone())
+            .field_types(&overrides)
+            .try_push_row(vec!["2021/7/20 PM 12:00:00".to_string(), "43".to_string()])
+            .unwrap()
+            .try_push_row(vec!["2021/7/20 PM 12:00:30".to_string(), "".to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        assert!(matches!(table.rows[0][1], CellValue::Int64(43)));
+        assert!(matches!(table.rows[1][1], CellValue::Null));
+        assert_eq!(table.rows[0][1].to_string(), "43");
+        assert_eq!(serde_json::to_value(&table).unwrap()[0]["Record Count"], 43);
+
+        let result = TableBuilder::new()
+            .field_names(field_names)
+            .field_types(&overrides)
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:00".to_string(),
+                "43.5".to_string(),
+            ]);
+        assert!(matches!(
+            result,
+            Err(AquaTrollLogError::InvalidInteger { column, value })
+                if column == "Record Count" && value == "43.5"
+        ));
+    }
+
+    #[test]
+    fn table_split_notes() {
+        let mut table = TableBuilder::new()
+            .field_names(vec![
+                "Date and Time".to_string(),
+                "Type".to_string(),
+                "Note".to_string(),
+                "Value".to_string(),
+            ])
+            .field_types(&[("Type".to_string(), ColumnType::Text)].into())
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:00".to_string(),
+                "Note".to_string(),
+                "Log Started".to_string(),
+                "".to_string(),
+            ])
+            .unwrap()
+            .try_push_row(vec![
+                "2021/7/20 PM 12:00:30".to_string(),
+                "Data".to_string(),
+                "".to_string(),
+                "1.0".to_string(),
+            ])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let notes = table.split_notes("Type", "Note").unwrap();
+        assert_eq!(notes.columns, vec!["DateTime", "Note"]);
+        assert_eq!(notes.num_rows(), 1);
+        assert!(matches!(&notes.rows[0][1], CellValue::Text(s) if s == "Log Started"));
+        assert_eq!(table.columns, vec!["DateTime", "Value"]);
+        assert_eq!(table.num_rows(), 1);
+        assert!(matches!(table.rows[0][1], CellValue::Float64(v) if v == 1.0));
+
+        assert!(table.split_notes("Type", "Note").is_none());
+    }
+
+    #[test]
+    fn table_builder_nullable_row() {
+        let table = TableBuilder::new()
+            .field_names(vec!["Date and Time".to_string(), "Value".to_string()])
+            .try_push_nullable_row(vec![Some("2021/7/20 PM 12:00:00".to_string()), None])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        assert!(matches!(table.rows[0][1], CellValue::Null));
+        assert_eq!(
+            table_to_json(&table, &JsonOptions::default())[0]["Value"],
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn table_to_json_timestamp_formats() {
+        let table = TableBuilder::new()
+            .field_names(vec!["Date and Time".to_string(), "Value".to_string()])
+            .try_push_row(vec!["2021/7/20 PM 12:00:00".to_string(), "1.0".to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let iso = table_to_json(&table, &JsonOptions::default());
+        assert_eq!(iso[0]["DateTime"], "2021-07-20T12:00:00");
+        assert_eq!(iso[0]["Value"], 1.0);
+
+        let epoch = table_to_json(
+            &table,
+            &JsonOptions {
+                timestamp_format: TimestampFormat::Epoch,
+                tz: None,
+            },
+        );
+        assert_eq!(epoch[0]["DateTime"], 1626782400);
+
+        let tz = FixedOffset::east_opt(8 * 3600);
+        let iso = table_to_json(
+            &table,
+            &JsonOptions {
+                timestamp_format: TimestampFormat::Iso8601,
+                tz,
+            },
+        );
+        assert_eq!(iso[0]["DateTime"], "2021-07-20T20:00:00+08:00");
+
+        let epoch = table_to_json(
+            &table,
+            &JsonOptions {
+                timestamp_format: TimestampFormat::Epoch,
+                tz,
+            },
+        );
+        assert_eq!(epoch[0]["DateTime"], 1626782400);
+
+        let table = Table {
+            timezone: FixedOffset::west_opt(5 * 3600),
+            ..table
+        };
+        let iso = table_to_json(&table, &JsonOptions::default());
+        assert_eq!(iso[0]["DateTime"], "2021-07-20T12:00:00-05:00");
+        let iso = table_to_json(
+            &table,
+            &JsonOptions {
+                timestamp_format: TimestampFormat::Iso8601,
+                tz,
+            },
+        );
+        assert_eq!(iso[0]["DateTime"], "2021-07-21T01:00:00+08:00");
+    }
 }
@@ -0,0 +1,247 @@
+use super::column::ColumnDescriptor;
+use super::common::{CellValue, Table};
+use super::quality::QualityTable;
+use crate::error::AquaTrollLogError;
+
+/// How tables reporting a parameter in different units are concatenated
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnitPolicy {
+    /// Fail with `InconsistentUnits`
+    #[default]
+    Error,
+    /// Convert readings into the unit of the first table
+    Convert,
+}
+
+/// Key matching a column across tables, the parameter (or the name of columns of unknown
+/// parameters) and its occurrence among columns of the same key
+fn column_keys(descriptors: &[ColumnDescriptor]) -> Vec<(String, usize)> {
+    let mut keys: Vec<(String, usize)> = Vec::with_capacity(descriptors.len());
+    for descriptor in descriptors {
+        let key = match &descriptor.parameter {
+            Some(parameter) => parameter.to_string(),
+            None => descriptor.name.clone(),
+        };
+        let nth = keys.iter().filter(|(k, _)| *k == key).count();
+        keys.push((key, nth));
+    }
+    keys
+}
+
+/// Index of each column of `first` among the columns of `other`
+fn match_columns(
+    first: &[ColumnDescriptor],
+    other: &[ColumnDescriptor],
+) -> Result<Vec<usize>, AquaTrollLogError> {
+    let first_keys = column_keys(first);
+    let other_keys = column_keys(other);
+    if let Some(i) = (0..other.len()).find(|&i| !first_keys.contains(&other_keys[i])) {
+        return Err(AquaTrollLogError::SchemaMismatch(other[i].name.clone()));
+    }
+
+    first_keys
+        .iter()
+        .zip(first)
+        .map(|(key, descriptor)| {
+            other_keys
+                .iter()
+                .position(|k| k == key)
+                .ok_or_else(|| AquaTrollLogError::SchemaMismatch(descriptor.name.clone()))
+        })
+        .collect()
+}
+
+fn inconsistent_units(first: &ColumnDescriptor, other: &ColumnDescriptor) -> AquaTrollLogError {
+    let unit_name = |d: &ColumnDescriptor| d.unit.as_ref().map(ToString::to_string);
+    AquaTrollLogError::InconsistentUnits {
+        column: first.name.clone(),
+        from: unit_name(other).unwrap_or_default(),
+        to: unit_name(first).unwrap_or_default(),
+    }
+}
+
+fn units_consistent(first: &ColumnDescriptor, other: &ColumnDescriptor) -> bool {
+    match (&first.unit, &other.unit) {
+        (Some(a), Some(b)) => a == b,
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+impl Table {
+    /// Check that columns of the tables match and report each parameter in the same unit
+    pub fn validate_units_consistent(tables: &[Table]) -> Result<(), AquaTrollLogError> {
+        let Some((first, rest)) = tables.split_first() else {
+            return Ok(());
+        };
+        let first = first.column_descriptors();
+
+        for table in rest {
+            let other = table.column_descriptors();
+            for (i, j) in match_columns(&first, &other)?.into_iter().enumerate() {
+                if !units_consistent(&first[i], &other[j]) {
+                    return Err(inconsistent_units(&first[i], &other[j]));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Concatenate rows of tables with the columns of the first table
+    ///
+    /// Columns are matched by parameter, or by name for columns of unknown parameters. The
+    /// time zone and column metadata are taken from the first table, timestamps of the other
+    /// tables are converted into the time zone of the first table (taken as UTC if unknown).
+    pub fn concat(tables: &[Table], policy: UnitPolicy) -> Result<Table, AquaTrollLogError> {
+        let Some((first, rest)) = tables.split_first() else {
+            return Err(AquaTrollLogError::InvalidData);
+        };
+        if policy == UnitPolicy::Error {
+            Table::validate_units_consistent(tables)?;
+        }
+        let mut result = first.clone();
+        let first_descriptors = first.column_descriptors();
+
+        for table in rest {
+            let other_descriptors = table.column_descriptors();
+            let indices = match_columns(&first_descriptors, &other_descriptors)?;
+            let offset = |t: &Table| t.timezone.map_or(0, |tz| tz.local_minus_utc());
+            let shift = chrono::Duration::seconds((offset(first) - offset(table)).into());
+
+            let mut conversions = Vec::with_capacity(indices.len());
+            for (i, &j) in indices.iter().enumerate() {
+                let (to, from) = (&first_descriptors[i], &other_descriptors[j]);
+                conversions.push(match (&from.unit, &to.unit) {
+                    _ if units_consistent(to, from) => None,
+                    (Some(from_unit), Some(to_unit)) => Some((from_unit, to_unit)),
+                    _ => return Err(inconsistent_units(to, from)),
+                });
+            }
+
+            for row in &table.rows {
+                let row = indices
+                    .iter()
+                    .zip(&conversions)
+                    .map(|(&j, conversion)| match (&row[j], conversion) {
+                        (CellValue::Float64(v), Some((from, to))) => {
+                            Ok(CellValue::Float64(from.convert(*v, to)?))
+                        }
+                        (CellValue::DateTime(dt), _) => Ok(CellValue::DateTime(*dt + shift)),
+                        (cell, _) => Ok(cell.clone()),
+                    })
+                    .collect::<Result<Vec<_>, AquaTrollLogError>>()?;
+                result.rows.push(row);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl QualityTable {
+    /// Concatenate quality tables of tables concatenated by `Table::concat`, in the columns of
+    /// the first table
+    ///
+    /// Cells of tables without a quality table are `None`.
+    pub(crate) fn concat(
+        tables: &[(&Table, Option<&QualityTable>)],
+    ) -> Result<QualityTable, AquaTrollLogError> {
+        let Some(((first, _), _)) = tables.split_first() else {
+            return Err(AquaTrollLogError::InvalidData);
+        };
+        let first_descriptors = first.column_descriptors();
+        let mut rows = vec![];
+
+        for (table, quality) in tables {
+            let indices = match_columns(&first_descriptors, &table.column_descriptors())?;
+            match quality {
+                Some(quality) => rows.extend(
+                    quality
+                        .rows
+                        .iter()
+                        .map(|row| indices.iter().map(|&j| row[j]).collect()),
+                ),
+                None => rows.extend(std::iter::repeat_n(
+                    vec![None; indices.len()],
+                    table.num_rows(),
+                )),
+            }
+        }
+
+        Ok(QualityTable {
+            columns: first.columns.clone(),
+            rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::TableBuilder;
+    use super::*;
+
+    fn pressure_table(column: &str, datetime: &str, value: &str) -> Table {
+        TableBuilder::new()
+            .field_names(vec!["Date and Time".to_string(), column.to_string()])
+            .try_push_row(vec![datetime.to_string(), value.to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap()
+    }
+
+    #[test]
+    fn concat_unit_policies() {
+        let tables = [
+            pressure_table("Pressure (PSI)", "2021/7/20 PM 12:00:00", "1.0"),
+            pressure_table("Pressure (kPa)", "2021/7/20 PM 12:00:30", "6.894757293168"),
+        ];
+
+        assert!(matches!(
+            Table::validate_units_consistent(&tables),
+            Err(AquaTrollLogError::InconsistentUnits { .. })
+        ));
+        assert!(matches!(
+            Table::concat(&tables, UnitPolicy::Error),
+            Err(AquaTrollLogError::InconsistentUnits { .. })
+        ));
+
+        let table = Table::concat(&tables, UnitPolicy::Convert).unwrap();
+        assert_eq!(table.columns, vec!["DateTime", "Pressure (PSI)"]);
+        assert_eq!(table.num_rows(), 2);
+        assert!(matches!(table.rows[1][1], CellValue::Float64(v) if (v - 1.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn concat_schema_mismatch() {
+        let tables = [
+            pressure_table("Pressure (PSI)", "2021/7/20 PM 12:00:00", "1.0"),
+            pressure_table("Depth (ft)", "2021/7/20 PM 12:00:30", "1.0"),
+        ];
+        assert!(matches!(
+            Table::concat(&tables, UnitPolicy::Convert),
+            Err(AquaTrollLogError::SchemaMismatch(_))
+        ));
+
+        let tables = [
+            pressure_table("Pressure (PSI)", "2021/7/20 PM 12:00:00", "1.0"),
+            pressure_table("Pressure (PSI)", "2021/7/20 PM 12:00:30", "2.0"),
+        ];
+        Table::validate_units_consistent(&tables).unwrap();
+        let table = Table::concat(&tables, UnitPolicy::Error).unwrap();
+        assert_eq!(table.num_rows(), 2);
+    }
+
+    #[test]
+    fn concat_time_zones() {
+        let mut first = pressure_table("Pressure (PSI)", "2021/7/20 PM 12:00:00", "1.0");
+        first.timezone = chrono::FixedOffset::east_opt(8 * 3600);
+        let mut other = pressure_table("Pressure (PSI)", "2021/7/20 AM 05:00:30", "2.0");
+        other.timezone = chrono::FixedOffset::east_opt(0);
+
+        let table = Table::concat(&[first, other], UnitPolicy::Error).unwrap();
+        assert_eq!(table.timezone, chrono::FixedOffset::east_opt(8 * 3600));
+        assert!(matches!(
+            table.rows[1][0],
+            CellValue::DateTime(dt) if dt.to_string() == "2021-07-20 13:00:30"
+        ));
+    }
+}
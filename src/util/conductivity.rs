@@ -0,0 +1,141 @@
+use serde_json::{Map, Value};
+
+use super::common::{CellValue, Table};
+use super::param::Parameter;
+use super::unit::Unit;
+use crate::error::AquaTrollLogError;
+
+/// Temperature compensation model of specific conductivity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConductivityModel {
+    /// Linear compensation of Standard Methods 2510, 1.91% per °C
+    StandardMethods,
+    /// Linear compensation with a temperature coefficient per °C
+    Linear(f64),
+}
+
+impl ConductivityModel {
+    fn coefficient(&self) -> f64 {
+        match self {
+            ConductivityModel::StandardMethods => 0.0191,
+            ConductivityModel::Linear(coefficient) => *coefficient,
+        }
+    }
+
+    /// Model named by the `Specific Conductivity Model` attribute (TXT logs), with the
+    /// `Temperature Coefficient` of the same section for linear models
+    pub fn from_attr(attr: &Map<String, Value>) -> Option<Self> {
+        let section = attr
+            .values()
+            .filter_map(Value::as_object)
+            .find(|section| section.contains_key("Specific Conductivity Model"))?;
+        let coefficient = section
+            .get("Temperature Coefficient")
+            .and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()));
+
+        match section["Specific Conductivity Model"].as_str()? {
+            "Standard Methods" => Some(ConductivityModel::StandardMethods),
+            "Linear" => coefficient.map(ConductivityModel::Linear),
+            _ => None,
+        }
+    }
+
+    /// Specific conductivity at `ref_temp` of an actual conductivity measured at
+    /// `temperature`, both in °C
+    pub fn specific_conductivity(&self, actual: f64, temperature: f64, ref_temp: f64) -> f64 {
+        actual / (1.0 + self.coefficient() * (temperature - ref_temp))
+    }
+}
+
+impl Table {
+    /// Compute specific conductivity at `ref_temp` °C of each row from the actual
+    /// conductivity and temperature columns, in the unit of the actual conductivity
+    ///
+    /// The temperature is taken from the sensor of the actual conductivity if it measures one.
+    pub fn derive_specific_conductivity(
+        &self,
+        model: ConductivityModel,
+        ref_temp: f64,
+    ) -> Result<Vec<Option<f64>>, AquaTrollLogError> {
+        let descriptors = self.column_descriptors();
+        let (conductivity_index, conductivity) = descriptors
+            .iter()
+            .enumerate()
+            .find(|(_, d)| matches!(d.parameter, Some(Parameter::ActualConductivity)))
+            .ok_or_else(|| {
+                AquaTrollLogError::ParameterNotFound(Parameter::ActualConductivity.to_string())
+            })?;
+        let temperatures: Vec<_> = descriptors
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| matches!(d.parameter, Some(Parameter::Temperature)))
+            .collect();
+        let (temperature_index, temperature) = temperatures
+            .iter()
+            .find(|(_, d)| d.sensor_serial == conductivity.sensor_serial)
+            .or(temperatures.first())
+            .copied()
+            .ok_or_else(|| {
+                AquaTrollLogError::ParameterNotFound(Parameter::Temperature.to_string())
+            })?;
+
+        self.rows
+            .iter()
+            .map(
+                |row| match (&row[conductivity_index], &row[temperature_index]) {
+                    (CellValue::Float64(actual), CellValue::Float64(t)) => {
+                        let t = match &temperature.unit {
+                            Some(unit) => unit.convert(*t, &Unit::Celsius)?,
+                            None => *t,
+                        };
+                        Ok(Some(model.specific_conductivity(*actual, t, ref_temp)))
+                    }
+                    _ => Ok(None),
+                },
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_compensation() {
+        let model = ConductivityModel::StandardMethods;
+        assert_eq!(model.specific_conductivity(500.0, 25.0, 25.0), 500.0);
+        assert!((model.specific_conductivity(500.0, 15.0, 25.0) - 618.047).abs() < 1e-3);
+        assert_eq!(
+            ConductivityModel::Linear(0.02).specific_conductivity(600.0, 30.0, 25.0),
+            545.4545454545454
+        );
+    }
+
+    #[test]
+    fn model_from_attr() {
+        let attr = serde_json::json!({
+            "Other Log Settings": {
+                "Specific Conductivity Model": "Standard Methods",
+                "Reference Temperature": "25",
+                "Temperature Coefficient": "0.0191"
+            }
+        });
+        assert_eq!(
+            ConductivityModel::from_attr(attr.as_object().unwrap()),
+            Some(ConductivityModel::StandardMethods)
+        );
+
+        let attr = serde_json::json!({
+            "Other Log Settings": {
+                "Specific Conductivity Model": "Linear",
+                "Temperature Coefficient": 0.021
+            }
+        });
+        assert_eq!(
+            ConductivityModel::from_attr(attr.as_object().unwrap()),
+            Some(ConductivityModel::Linear(0.021))
+        );
+        assert_eq!(ConductivityModel::from_attr(&Map::new()), None);
+    }
+}
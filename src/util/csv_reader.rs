@@ -1,18 +1,51 @@
-use std::io::{BufRead, Seek};
+use std::io::{BufRead, Read, Seek};
 
 use csv::ErrorKind;
 use csv::StringRecord;
 
 use crate::error::AquaTrollLogError;
 
-use super::common::{DateTimeParser, Table, TableBuilder};
+use super::common::{is_datetime_header_with, ReadOptions, Table, TableBuilder};
+use super::sensor::SensorInfo;
+use super::warning::ReadWarning;
 
-#[derive(thiserror::Error, Debug)]
+/// Layout of CSV files and handling of rows of a different length than the header, such rows
+/// are dropped and reported in `WithCsvPartialResult` by default
+#[derive(Debug, Clone)]
+pub struct CsvReadOptions {
+    /// Keep rows shorter than the header, missing trailing cells are null
+    pub pad_short_rows: bool,
+    /// Keep rows longer than the header, extra trailing cells are skipped
+    pub skip_extra_columns: bool,
+    /// Field delimiter, `b','` by default, e.g. `b';'` of European locale exports
+    pub delimiter: u8,
+    /// Read readings of comma decimals, e.g. `21,6019` of European locale exports
+    pub decimal_comma: bool,
+}
+
+impl Default for CsvReadOptions {
+    fn default() -> Self {
+        Self {
+            pad_short_rows: false,
+            skip_extra_columns: false,
+            delimiter: b',',
+            decimal_comma: false,
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct ErrorWithCsvPartialResult {
     pub(crate) result: Box<Table>,
     pub(crate) errors: Vec<csv::Error>,
 }
 
+impl std::error::Error for ErrorWithCsvPartialResult {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.errors.first().map(|e| e as _)
+    }
+}
+
 impl std::fmt::Display for ErrorWithCsvPartialResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "CSV error with partial result:")?;
@@ -24,13 +57,43 @@ impl std::fmt::Display for ErrorWithCsvPartialResult {
     }
 }
 
-/// Read csv log data
+/// Read parameters of the csv header as a single sensor, rows are not read
+///
+/// CSV logs don't carry sensor serials, so all the parameters are taken as of an unknown sensor.
+pub(crate) fn read_csv_sensors<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<Vec<SensorInfo>, AquaTrollLogError> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .delimiter(options.csv.delimiter)
+        .from_reader(reader);
+
+    let parameters: Vec<String> = csv_reader
+        .headers()?
+        .iter()
+        .filter(|name| !is_datetime_header_with(name, options.lenient_header_match))
+        .map(|name| name.to_string())
+        .collect();
+    if parameters.is_empty() {
+        return Ok(vec![]);
+    }
+
+    Ok(vec![SensorInfo {
+        parameters,
+        ..Default::default()
+    }])
+}
+
+/// Read csv log data, warnings of padded and truncated rows are appended to `warnings`
 pub(crate) fn read_table<R: BufRead + Seek>(
     reader: &mut R,
-    datetime_parser: &DateTimeParser,
+    options: &ReadOptions,
+    warnings: &mut Vec<ReadWarning>,
 ) -> Result<Table, AquaTrollLogError> {
     let mut csv_reader = csv::ReaderBuilder::new()
         .has_headers(true)
+        .delimiter(options.csv.delimiter)
         .from_reader(reader);
 
     let fields: Vec<String> = csv_reader
@@ -41,8 +104,14 @@ pub(crate) fn read_table<R: BufRead + Seek>(
     let fields_len = fields.len();
 
     let mut table_builder = TableBuilder::new()
+        .with_lenient_header_match(options.lenient_header_match)
         .field_names(fields.clone())
-        .with_datetime_parser(datetime_parser.clone());
+        .field_types(&options.column_types())
+        .with_datetime_parser(options.datetime_parser.clone())
+        .with_timezone(options.timezone)
+        .with_infinite_as_null(options.infinite_as_null)
+        .with_decimal_comma(options.csv.decimal_comma)
+        .with_datetime_column_name(options.datetime_column_name.clone());
     let mut record = StringRecord::new();
     let mut csv_errors: Vec<csv::Error> = Vec::new();
 
@@ -58,7 +127,23 @@ pub(crate) fn read_table<R: BufRead + Seek>(
                 }
             }
             Err(e) if matches!(e.kind(), ErrorKind::UnequalLengths { .. }) => {
-                csv_errors.push(e);
+                // The record is still read on unequal lengths
+                let csv_options = &options.csv;
+                let mut values: Vec<Option<String>> =
+                    record.iter().map(|v| Some(v.to_string())).collect();
+                if (values.len() < fields_len && csv_options.pad_short_rows)
+                    || (values.len() > fields_len && csv_options.skip_extra_columns)
+                {
+                    warnings.push(ReadWarning::ResizedRow {
+                        line_number: e.position().map(|p| p.line() as usize),
+                        expected: fields_len,
+                        found: values.len(),
+                    });
+                    values.resize(fields_len, None);
+                    table_builder = table_builder.try_push_nullable_row(values)?;
+                } else {
+                    csv_errors.push(e);
+                }
             }
             Err(e) => return Err(e.into()),
         }
@@ -77,6 +162,8 @@ pub(crate) fn read_table<R: BufRead + Seek>(
 
 #[cfg(test)]
 mod tests {
+    use super::super::common::CellValue;
+    use super::super::unit::Unit;
     use super::*;
     use std::io::Cursor;
 
@@ -94,7 +181,7 @@ mod tests {
     #[test]
     fn test_read_table() {
         let mut reader = Cursor::new(LOG_DATA_CSV);
-        let data_table = read_table(&mut reader, &DateTimeParser::Default).unwrap();
+        let data_table = read_table(&mut reader, &ReadOptions::default(), &mut vec![]).unwrap();
         assert_eq!(
             data_table.columns,
             vec![
@@ -112,6 +199,19 @@ mod tests {
             ]
         );
         assert_eq!(data_table.num_rows(), 8);
+        assert_eq!(
+            data_table.column_descriptors()[4].unit,
+            Some(Unit::OhmCentimeters)
+        );
+    }
+
+    #[test]
+    fn csv_sensors() {
+        let sensors = read_csv_sensors(LOG_DATA_CSV.as_bytes(), &ReadOptions::default()).unwrap();
+        assert_eq!(sensors.len(), 1);
+        assert_eq!(sensors[0].serial, None);
+        assert_eq!(sensors[0].parameters.len(), 10);
+        assert_eq!(sensors[0].parameters[0], "Temp(C)");
     }
 
     static LOG_DATA_MULTIPLE_HEADERS_CSV: &str = r#"Date/Time,Temp(C),CNDCT(µS/cm),SPCNDCT(µS/cm),R(ohm-cm),SA(PSU),TDS(ppm),pH(pH),ORP(mV),DO(con)(mg/L),DO(%sat)(%Sat)
@@ -128,10 +228,27 @@ Date/Time,Temp(C),CNDCT(µS/cm),SPCNDCT(µS/cm),R(ohm-cm),SA(PSU),TDS(ppm),pH(pH
     #[test]
     fn test_read_multiple_headers_table() {
         let mut reader = Cursor::new(LOG_DATA_MULTIPLE_HEADERS_CSV);
-        let data_table = read_table(&mut reader, &DateTimeParser::Default).unwrap();
+        let data_table = read_table(&mut reader, &ReadOptions::default(), &mut vec![]).unwrap();
         assert_eq!(data_table.num_rows(), 6);
     }
 
+    static LOG_DATA_EMPTY_CELL_CSV: &str = r#"Date/Time,Temp(C),CNDCT(µS/cm),SPCNDCT(µS/cm)
+2025/1/25 05:15:06 PM,21.6019,416.245,445.136
+2025/1/25 05:15:36 PM,21.6097,,---
+2025/1/25 05:16:06 PM,21.6239,416.77, 
+"#;
+
+    #[test]
+    fn test_read_empty_cell_table() {
+        let mut reader = Cursor::new(LOG_DATA_EMPTY_CELL_CSV);
+        let data_table = read_table(&mut reader, &ReadOptions::default(), &mut vec![]).unwrap();
+        assert_eq!(data_table.num_rows(), 3);
+        assert!(matches!(data_table.rows[0][2], CellValue::Float64(v) if v == 416.245));
+        assert!(matches!(data_table.rows[1][2], CellValue::Null));
+        assert!(matches!(data_table.rows[1][3], CellValue::Null));
+        assert!(matches!(data_table.rows[2][3], CellValue::Null));
+    }
+
     static LOG_DATA_INCOMPLETE_CSV: &str = r#"Date/Time,Temp(C),CNDCT(µS/cm),SPCNDCT(µS/cm),R(ohm-cm),SA(PSU),TDS(ppm),pH(pH),ORP(mV),DO(con)(mg/L),DO(%sat)(%Sat)
 2025/1/25 05:15:06 PM,21.6019,416.245,445.136,2402.43,0.216156,289.339,7.40582,173.966,5.43175,56.0774
 2025/1/25 05:15:36 PM,21.6097,416.924,445.791,2398.52,0.216483,289.764,7.40086,172.221,5.33604,55.0975
@@ -144,10 +261,125 @@ Date/Time,Temp(C),CNDCT(µS/cm),SPCNDCT(µS/cm),R(ohm-cm),SA(PSU),TDS(ppm),pH(pH
     #[test]
     fn test_read_incomplete_table() {
         let mut reader = Cursor::new(LOG_DATA_INCOMPLETE_CSV);
-        let data_table = match read_table(&mut reader, &DateTimeParser::Default) {
+        let data_table = match read_table(&mut reader, &ReadOptions::default(), &mut vec![]) {
             Err(AquaTrollLogError::WithCsvPartialResult(partial_result)) => partial_result.result,
             _ => panic!("Expected a CSV error with partial result"),
         };
         assert_eq!(data_table.num_rows(), 4);
     }
+
+    static LOG_DATA_QUOTED_NOTE_CSV: &str = r#"Date/Time,Temp(C),Note
+2025/1/25 05:15:06 PM,21.6019,"Sensor cleaned, recalibrated, redeployed"
+2025/1/25 05:15:36 PM,21.6097,"Battery low,
+replaced"
+2025/1/25 05:16:06 PM,21.6239,"Quoted ""Note"", kept"
+2025/1/25 05:16:36 PM,21.6365,
+"#;
+
+    #[test]
+    fn test_read_quoted_note_table() {
+        let mut reader = Cursor::new(LOG_DATA_QUOTED_NOTE_CSV);
+        let data_table = read_table(&mut reader, &ReadOptions::default(), &mut vec![]).unwrap();
+        assert_eq!(data_table.num_rows(), 4);
+        assert!(matches!(&data_table.rows[0][2],
+            CellValue::Text(s) if s == "Sensor cleaned, recalibrated, redeployed"));
+        assert!(
+            matches!(&data_table.rows[1][2], CellValue::Text(s) if s == "Battery low,\nreplaced")
+        );
+        assert!(
+            matches!(&data_table.rows[2][2], CellValue::Text(s) if s == "Quoted \"Note\", kept")
+        );
+        assert!(matches!(data_table.rows[1][1], CellValue::Float64(v) if v == 21.6097));
+        assert!(matches!(&data_table.rows[3][2], CellValue::Text(s) if s.is_empty()));
+    }
+
+    #[test]
+    fn test_read_incomplete_table_padded() {
+        let mut reader = Cursor::new(LOG_DATA_INCOMPLETE_CSV);
+        let options = ReadOptions {
+            csv: CsvReadOptions {
+                pad_short_rows: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let data_table = read_table(&mut reader, &options, &mut vec![]).unwrap();
+        assert_eq!(data_table.num_rows(), 5);
+        assert!(matches!(data_table.rows[2][8], CellValue::Float64(v) if v == 169.58));
+        assert!(matches!(data_table.rows[2][9], CellValue::Null));
+        assert!(matches!(data_table.rows[2][10], CellValue::Null));
+    }
+
+    #[test]
+    fn test_read_table_extra_columns() {
+        let csv = "Date/Time,Temp(C)\n\
+                   2025/1/25 05:15:06 PM,21.6019,1.0\n\
+                   2025/1/25 05:15:36 PM,21.6097\n";
+
+        let mut reader = Cursor::new(csv);
+        assert!(matches!(
+            read_table(&mut reader, &ReadOptions::default(), &mut vec![]),
+            Err(AquaTrollLogError::WithCsvPartialResult(_))
+        ));
+
+        let mut reader = Cursor::new(csv);
+        let options = ReadOptions {
+            csv: CsvReadOptions {
+                skip_extra_columns: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let data_table = read_table(&mut reader, &options, &mut vec![]).unwrap();
+        assert_eq!(data_table.num_rows(), 2);
+        assert_eq!(data_table.rows[0].len(), 2);
+        assert!(matches!(data_table.rows[0][1], CellValue::Float64(v) if v == 21.6019));
+    }
+
+    #[test]
+    fn test_read_table_decimal_comma() {
+        let csv = "Date/Time;Temp(C);CNDCT(µS/cm);Note\n\
+                   2025/1/25 05:15:06 PM;21,6019;416,245;a, b\n";
+        let options = ReadOptions {
+            csv: CsvReadOptions {
+                delimiter: b';',
+                decimal_comma: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let data_table = read_table(&mut Cursor::new(csv), &options, &mut vec![]).unwrap();
+        assert_eq!(data_table.columns[1], "Temp(C)");
+        assert!(matches!(data_table.rows[0][1], CellValue::Float64(v) if v == 21.6019));
+        assert!(matches!(data_table.rows[0][2], CellValue::Float64(v) if v == 416.245));
+        assert!(matches!(&data_table.rows[0][3], CellValue::Text(s) if s == "a, b"));
+        assert_eq!(
+            read_csv_sensors(csv.as_bytes(), &options).unwrap()[0].parameters,
+            vec!["Temp(C)", "CNDCT(µS/cm)", "Note"]
+        );
+    }
+
+    #[test]
+    fn test_read_table_lenient_header_match() {
+        // Headers are matched exactly unless lenient
+        let csv = "Date/Time ,Temp(C)\n2025/1/25 05:15:06 PM,21.6019\n";
+        assert!(read_table(&mut Cursor::new(csv), &ReadOptions::default(), &mut vec![]).is_err());
+        let options = ReadOptions {
+            lenient_header_match: true,
+            ..Default::default()
+        };
+        let data_table = read_table(&mut Cursor::new(csv), &options, &mut vec![]).unwrap();
+        assert_eq!(data_table.columns, vec!["DateTime", "Temp(C)"]);
+
+        let csv = " date/time,Temp(C), NOTE\n2025/1/25 05:15:06 PM,21.6019,Foo\n";
+        assert!(read_table(&mut Cursor::new(csv), &ReadOptions::default(), &mut vec![]).is_err());
+        let data_table = read_table(&mut Cursor::new(csv), &options, &mut vec![]).unwrap();
+        assert_eq!(data_table.columns, vec!["DateTime", "Temp(C)", " NOTE"]);
+        assert!(matches!(data_table.rows[0][0], CellValue::DateTime(_)));
+        assert!(matches!(&data_table.rows[0][2], CellValue::Text(s) if s == "Foo"));
+        assert_eq!(
+            read_csv_sensors(csv.as_bytes(), &options).unwrap()[0].parameters,
+            vec!["Temp(C)", " NOTE"]
+        );
+    }
 }
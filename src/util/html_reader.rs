@@ -1,34 +1,146 @@
+use std::collections::HashMap;
 use std::io::{Read, Seek};
 
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
 use num_traits::FromPrimitive;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use serde_json::{json, Map, Value};
 
-use super::common::{DateTimeParser, Table, TableBuilder};
+use super::common::{
+    parse_time_zone, CellValue, ReadOptions, Table, TableBuilder, DATETIME_COLUMN,
+    DEVICE_SERIAL_KEY, SENSOR_SERIAL_KEY, SENSOR_TYPE_KEY,
+};
 use super::param::Parameter;
+use super::quality::{DataQuality, QualityTable};
+use super::sensor::SensorInfo;
 use super::unit::Unit;
+use super::warning::ReadWarning;
 use crate::error::AquaTrollLogError;
 
+/// Attributes, log data, data quality codes and read warnings of a HTML log file
+type HtmlLog = (Map<String, Value>, Table, QualityTable, Vec<ReadWarning>);
+
+/// Parse `label = value` (or `label: value`) text of a section member row, falling back to
+/// the `isi-label` and `isi-value` spans
+fn parse_section_member(row: ElementRef) -> Option<(String, String)> {
+    let text = row.text().collect::<String>();
+    if let Some((k, v)) = text.split_once('=').or_else(|| text.split_once(':')) {
+        return Some((k.trim().to_string(), v.trim().to_string()));
+    }
+
+    let label_selector = Selector::parse("[isi-label]").unwrap();
+    let value_selector = Selector::parse("[isi-value]").unwrap();
+    let label = row
+        .select(&label_selector)
+        .next()?
+        .text()
+        .collect::<String>();
+    let value = row
+        .select(&value_selector)
+        .next()?
+        .text()
+        .collect::<String>();
+
+    Some((label.trim().to_string(), value.trim().to_string()))
+}
+
+/// Parse the integer code of an attribute, e.g. `isi-parameter-type`
+///
+/// Absent attributes are `None` silently, while malformed codes are `None` with a warning
+/// appended to `warnings`.
+fn parse_code<T: std::str::FromStr>(
+    cell: ElementRef,
+    name: &str,
+    warnings: &mut Vec<ReadWarning>,
+) -> Option<T> {
+    let value = cell.attr(name)?;
+    match value.trim().parse() {
+        Ok(code) => Some(code),
+        Err(_) => {
+            warnings.push(ReadWarning::MalformedCode {
+                attribute: name.to_string(),
+                value: value.to_string(),
+            });
+            None
+        }
+    }
+}
+
+/// Field name of a parameter column, e.g. `pH (pH)`
+fn parameter_field_name(param: Parameter, unit: Option<Unit>) -> String {
+    match unit {
+        Some(u) => format!("{} ({})", param, u),
+        None => param.to_string(),
+    }
+}
+
+/// Ticks of `isi-timestamp` per second
+const TICKS_PER_SECOND: i64 = 1 << 16;
+
+/// Timestamp of an `isi-timestamp` device tick, of 1/65536 seconds since the Unix epoch in UTC,
+/// in the `timezone` (UTC if unknown)
+fn tick_datetime(tick: i64, timezone: Option<FixedOffset>) -> Option<NaiveDateTime> {
+    let nanos = tick.rem_euclid(TICKS_PER_SECOND) * 1_000_000_000 / TICKS_PER_SECOND;
+    let datetime = DateTime::from_timestamp(tick.div_euclid(TICKS_PER_SECOND), nanos as u32)?;
+    Some(match timezone {
+        Some(timezone) => datetime.with_timezone(&timezone).naive_local(),
+        None => datetime.naive_utc(),
+    })
+}
+
+/// Typed attributes of the machine readable values of a section member, the milliseconds of
+/// `isi-timespan-milliseconds` and the UTC date time of `isi-timestamp` ticks
+fn section_member_typed_attrs(cell: ElementRef, label: &str) -> Vec<(String, Value)> {
+    let mut typed_attrs = vec![];
+    if let Some(ms) = cell
+        .attr("isi-timespan-milliseconds")
+        .and_then(|v| v.parse::<i64>().ok())
+    {
+        typed_attrs.push((format!("{label} (ms)"), Value::from(ms)));
+    }
+    if let Some(datetime) = cell
+        .attr("isi-timestamp")
+        .and_then(|v| tick_datetime(v.parse().ok()?, None))
+    {
+        typed_attrs.push((
+            format!("{label} (UTC)"),
+            Value::String(datetime.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string()),
+        ));
+    }
+    typed_attrs
+}
+
 // Log reader for In-Situ HTML files
 // ref: https://in-situ.com/en/html-parsing-guide
 pub(crate) fn read_html<R: Read>(
     reader: &mut R,
-    datetime_parser: &DateTimeParser,
-) -> Result<(Map<String, Value>, Table), AquaTrollLogError> {
+    options: &ReadOptions,
+) -> Result<HtmlLog, AquaTrollLogError> {
     let mut buf = vec![];
     let _ = reader.read_to_end(&mut buf)?;
 
     let mut attr_headers: Vec<String> = vec![];
     let mut attrs: Vec<Map<String, Value>> = vec![];
     let mut sensors: Vec<(String, u32, u64)> = vec![];
+    // Columns to be read, others are filtered out by sensor serial
+    let mut selected_columns: Vec<bool> = vec![];
+    let mut quality_rows: Vec<Vec<Option<DataQuality>>> = vec![];
+    // `isi-timestamp` ticks of data rows
+    let mut ticks: Vec<Option<i64>> = vec![];
+    let mut warnings: Vec<ReadWarning> = vec![];
 
     // convert bytes into string
     let html = String::from_utf8(buf)?;
     let document = Html::parse_document(&html);
     let header_selector = Selector::parse("table#isi-report tr").unwrap();
+    let header_cell_selector = Selector::parse("table#isi-report td, table#isi-report th").unwrap();
     let data_selector = Selector::parse("table#isi-report td").unwrap();
 
-    let mut table_builder = TableBuilder::new().with_datetime_parser(datetime_parser.clone());
+    let mut table_builder = TableBuilder::new()
+        .with_datetime_parser(options.datetime_parser.clone())
+        .with_timezone(options.timezone)
+        .with_infinite_as_null(options.infinite_as_null)
+        .with_datetime_column_name(options.datetime_column_name.clone());
 
     for row in document.select(&header_selector) {
         let is_section_header = row
@@ -48,45 +160,71 @@ pub(crate) fn read_html<R: Read>(
             let cur_attr = attrs
                 .last_mut()
                 .ok_or(AquaTrollLogError::SectionHeaderNotFound)?;
-            row.text()
-                .collect::<String>()
-                .split_once("=")
-                .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
-                .map(|(k, v)| cur_attr.insert(k, Value::String(v)))
-                .ok_or(AquaTrollLogError::InvalidData)?;
+            let Some((k, v)) = parse_section_member(row) else {
+                let text = row.text().collect::<String>();
+                warnings.push(ReadWarning::UnparsedAttribute(text.trim().to_string()));
+                continue;
+            };
+            let typed_attrs = match row.child_elements().next() {
+                Some(cell) if options.typed_attrs => section_member_typed_attrs(cell, &k),
+                _ => vec![],
+            };
+            cur_attr.insert(k, options.attr_value(&v));
+            cur_attr.extend(typed_attrs);
         } else if is_data_header {
             let mut fields: Vec<String> = Vec::new();
+            let mut column_metadata: Vec<HashMap<String, String>> = vec![];
+            let mut device_serials: Vec<Option<u64>> = vec![];
 
-            for cell in row.select(&data_selector) {
+            for cell in row.select(&header_cell_selector) {
                 let attr = cell.attr("isi-data-column-header").unwrap_or("");
-                let param = cell
-                    .attr("isi-parameter-type")
-                    .and_then(|v| v.parse().ok())
+                let param = parse_code(cell, "isi-parameter-type", &mut warnings)
                     .and_then(Parameter::from_u8);
-                let unit = cell
-                    .attr("isi-unit-type")
-                    .and_then(|v| v.parse().ok())
-                    .and_then(Unit::from_u16);
-                let sensor_type: Option<u32> =
-                    cell.attr("isi-sensor-type").and_then(|v| v.parse().ok());
-                let serial: Option<u64> = cell
-                    .attr("isi-sensor-serial-number")
-                    .and_then(|v| v.parse().ok());
-
-                let field_name = match (param, unit) {
-                    (Some(p), Some(u)) => {
+                let unit =
+                    parse_code(cell, "isi-unit-type", &mut warnings).and_then(Unit::from_u16);
+                let sensor_type: Option<u32> = parse_code(cell, "isi-sensor-type", &mut warnings);
+                let serial: Option<u64> =
+                    parse_code(cell, "isi-sensor-serial-number", &mut warnings);
+                let device_serial: Option<u64> =
+                    parse_code(cell, "isi-device-serial-number", &mut warnings);
+
+                let is_selected = options.accepts_sensor(serial);
+                selected_columns.push(is_selected);
+                if !is_selected {
+                    continue;
+                }
+
+                let mut metadata = HashMap::new();
+                if let Some(serial) = serial {
+                    metadata.insert(SENSOR_SERIAL_KEY.to_string(), serial.to_string());
+                }
+                if let Some(device_serial) = device_serial {
+                    metadata.insert(DEVICE_SERIAL_KEY.to_string(), device_serial.to_string());
+                }
+                if let Some(sensor_type) = sensor_type {
+                    metadata.insert(SENSOR_TYPE_KEY.to_string(), sensor_type.to_string());
+                }
+                column_metadata.push(metadata);
+                device_serials.push(device_serial);
+
+                let field_name = match (param, unit.is_some()) {
+                    (Some(p), true) => {
                         // Collect sensor information if both serial and type are present
                         match (serial, sensor_type) {
                             (Some(s), Some(t)) => sensors.push((p.to_string(), t, s)),
-                            (None, Some(_)) => tracing::warn!("{}: Sensor serial not found", p),
-                            (Some(_), None) => tracing::warn!("{}: Sensor type not found", p),
+                            (None, Some(_)) => {
+                                warnings.push(ReadWarning::SensorSerialNotFound(p.to_string()))
+                            }
+                            (Some(_), None) => {
+                                warnings.push(ReadWarning::SensorTypeNotFound(p.to_string()))
+                            }
                             (None, None) => {}
                         }
-                        format!("{} ({})", p, u)
+                        parameter_field_name(p, unit)
                     }
-                    (Some(p), None) => p.to_string(),
+                    (Some(p), false) => parameter_field_name(p, None),
                     (None, _) => match attr {
-                        "DateTime" => "DateTime".to_string(),
+                        DATETIME_COLUMN => DATETIME_COLUMN.to_string(),
                         "Marked" => "Marked".to_string(),
                         _ => {
                             let n_unknown =
@@ -102,18 +240,67 @@ pub(crate) fn read_html<R: Read>(
                 fields.push(field_name);
             }
 
-            table_builder = table_builder.field_names(fields);
+            // Names of columns of logs combining devices are suffixed with the device serial,
+            // e.g. `pH (pH) (111111)`
+            let first_device = device_serials.iter().flatten().next();
+            if device_serials
+                .iter()
+                .flatten()
+                .any(|device| Some(device) != first_device)
+            {
+                for (field, device) in fields.iter_mut().zip(&device_serials) {
+                    if let Some(device) = device {
+                        field.push_str(&format!(" ({device})"));
+                    }
+                }
+            }
+
+            table_builder = table_builder
+                .field_names(fields)
+                .field_types(&options.column_types())
+                .with_column_metadata(column_metadata);
         } else if is_data {
-            let data = row
+            let (data, qualities): (Vec<_>, Vec<_>) = row
                 .select(&data_selector)
-                .map(|h| h.text().collect::<String>())
-                .collect();
+                .enumerate()
+                .filter(|(i, _)| selected_columns.get(*i).copied().unwrap_or(true))
+                .map(|(_, cell)| {
+                    let quality = cell
+                        .attr("isi-data-quality")
+                        .and_then(|v| v.parse().ok())
+                        .map(DataQuality);
+                    let value = match (quality, options.null_below_quality) {
+                        (Some(q), Some(threshold)) if q < threshold => None,
+                        _ => Some(cell.text().collect::<String>()),
+                    };
+                    (value, quality)
+                })
+                .unzip();
 
-            table_builder = table_builder.try_push_row(data)?;
+            table_builder = table_builder.try_push_nullable_row(data)?;
+            quality_rows.push(qualities);
+            ticks.push(row.attr("isi-timestamp").and_then(|t| t.parse().ok()));
         }
     }
 
-    let log_data = table_builder.try_build()?;
+    let mut log_data = table_builder.try_build()?;
+    if log_data.timezone.is_none() {
+        // Offset of timestamps to UTC, e.g. `08:00:00`
+        log_data.timezone = attrs
+            .iter()
+            .find_map(|attr| attr.get("Time Offset"))
+            .and_then(Value::as_str)
+            .and_then(parse_time_zone);
+    }
+    if options.tick_timestamps {
+        if let Some(index) = log_data.datetime_column() {
+            for (row, tick) in log_data.rows.iter_mut().zip(ticks) {
+                if let Some(datetime) = tick.and_then(|t| tick_datetime(t, log_data.timezone)) {
+                    row[index] = CellValue::DateTime(datetime);
+                }
+            }
+        }
+    }
 
     if !sensors.is_empty() {
         attr_headers.push("Log Data".to_string());
@@ -141,17 +328,117 @@ pub(crate) fn read_html<R: Read>(
         attr.insert(k, Value::Object(v));
     }
 
-    Ok((attr, log_data))
+    let log_quality = QualityTable {
+        columns: log_data.columns.clone(),
+        rows: quality_rows,
+    };
+
+    Ok((attr, log_data, log_quality, warnings))
 }
 
+/// Read sensors of the data header and the columns they measure, data rows are not read
+pub(crate) fn read_html_sensors<R: Read>(
+    reader: &mut R,
+) -> Result<Vec<SensorInfo>, AquaTrollLogError> {
+    let mut buf = vec![];
+    let _ = reader.read_to_end(&mut buf)?;
+
+    let html = String::from_utf8(buf)?;
+    let document = Html::parse_document(&html);
+    let header_cell_selector = Selector::parse(
+        "table#isi-report tr[isi-data-table] td, table#isi-report tr[isi-data-table] th",
+    )
+    .unwrap();
+
+    let mut sensors: Vec<SensorInfo> = vec![];
+    // Malformed codes are ignored, as of reading the log
+    let warnings = &mut vec![];
+    for cell in document.select(&header_cell_selector) {
+        let Some(serial) = parse_code::<u64>(cell, "isi-sensor-serial-number", warnings) else {
+            continue;
+        };
+        let sensor_type: Option<u32> = parse_code(cell, "isi-sensor-type", warnings);
+        let device_serial: Option<u64> = parse_code(cell, "isi-device-serial-number", warnings);
+        let parameter = parse_code(cell, "isi-parameter-type", warnings)
+            .and_then(Parameter::from_u8)
+            .map(|p| {
+                let unit = parse_code(cell, "isi-unit-type", warnings).and_then(Unit::from_u16);
+                parameter_field_name(p, unit)
+            });
+
+        // Sensors are told apart by device too, of logs combining devices
+        let index = match sensors
+            .iter()
+            .position(|s| s.serial == Some(serial) && s.device_serial == device_serial)
+        {
+            Some(index) => index,
+            None => {
+                sensors.push(SensorInfo {
+                    serial: Some(serial),
+                    device_serial,
+                    sensor_type,
+                    ..Default::default()
+                });
+                sensors.len() - 1
+            }
+        };
+        sensors[index].parameters.extend(parameter);
+    }
+
+    Ok(sensors)
+}
+
+/// Indices of HTML files in the zip archive, in archive order
+fn html_entries<R: Read + Seek>(zip: &zip::ZipArchive<R>) -> Vec<usize> {
+    (0..zip.len())
+        .filter(|&i| {
+            zip.name_for_index(i)
+                .map(|name| name.to_ascii_lowercase())
+                .is_some_and(|name| name.ends_with(".html") || name.ends_with(".htm"))
+        })
+        .collect()
+}
+
+/// Read the first HTML file in the zip archive
 pub(crate) fn read_zipped_html<R: Read + Seek>(
     reader: R,
-    datetime_parser: &DateTimeParser,
-) -> Result<(Map<String, Value>, Table), AquaTrollLogError> {
+    options: &ReadOptions,
+) -> Result<HtmlLog, AquaTrollLogError> {
     let mut zip = zip::ZipArchive::new(reader)?;
-    let mut html_file = zip.by_index(0)?;
+    let index = html_entries(&zip)
+        .first()
+        .copied()
+        .ok_or(zip::result::ZipError::FileNotFound)?;
+    let mut html_file = zip.by_index(index)?;
 
-    read_html(&mut html_file, datetime_parser)
+    read_html(&mut html_file, options)
+}
+
+/// Read sensors of the first HTML file in the zip archive
+pub(crate) fn read_zipped_html_sensors<R: Read + Seek>(
+    reader: R,
+) -> Result<Vec<SensorInfo>, AquaTrollLogError> {
+    let mut zip = zip::ZipArchive::new(reader)?;
+    let index = html_entries(&zip)
+        .first()
+        .copied()
+        .ok_or(zip::result::ZipError::FileNotFound)?;
+    let mut html_file = zip.by_index(index)?;
+
+    read_html_sensors(&mut html_file)
+}
+
+/// Read all HTML files in the zip archive
+pub(crate) fn read_zipped_html_all<R: Read + Seek>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<Vec<HtmlLog>, AquaTrollLogError> {
+    let mut zip = zip::ZipArchive::new(reader)?;
+
+    html_entries(&zip)
+        .into_iter()
+        .map(|i| read_html(&mut zip.by_index(i)?, options))
+        .collect()
 }
 
 #[cfg(test)]
@@ -160,6 +447,8 @@ mod tests {
 
     use serde_json::json;
 
+    use super::super::common::CellValue;
+    use super::super::sensor::SensorType;
     use super::*;
 
     const TEST_CONTENT: &str = r#"
@@ -207,7 +496,7 @@ mod tests {
     #[test]
     fn log_html() {
         let mut reader = Cursor::new(TEST_CONTENT.as_bytes());
-        let (attr, log_data) = read_html(&mut reader, &DateTimeParser::Default).unwrap();
+        let (attr, log_data, _, _) = read_html(&mut reader, &ReadOptions::default()).unwrap();
 
         // Check attributes of log file
         assert_eq!(
@@ -353,4 +642,359 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn log_html_parameters() {
+        let mut reader = Cursor::new(TEST_CONTENT.as_bytes());
+        let (_, log_data, _, _) = read_html(&mut reader, &ReadOptions::default()).unwrap();
+        assert_eq!(
+            log_data.parameters(),
+            vec![
+                Parameter::ActualConductivity,
+                Parameter::SpecificConductivity,
+                Parameter::Salinity,
+                Parameter::Resistivity,
+                Parameter::DensityOfWater,
+                Parameter::TotalDissolvedSolids,
+                Parameter::DissolvedOxygenConcentration,
+                Parameter::DissolvedOxygenPercentSaturation,
+                Parameter::OxygenPartialPressure,
+                Parameter::PH,
+                Parameter::PHmV,
+                Parameter::OxidationReductionPotential,
+                Parameter::Turbidity,
+                Parameter::Temperature,
+                Parameter::BarometricPressure,
+                Parameter::Pressure,
+                Parameter::Depth,
+                Parameter::ExternalVoltage,
+                Parameter::BatteryCapacityRemaining,
+            ]
+        );
+
+        // Temperature of another sensor in place of depth
+        let content = TEST_CONTENT.replace(
+            r#"isi-parameter-type="3" isi-unit-type="35""#,
+            r#"isi-parameter-type="1" isi-unit-type="1""#,
+        );
+        let mut reader = Cursor::new(content.as_bytes());
+        let (_, log_data, _, _) = read_html(&mut reader, &ReadOptions::default()).unwrap();
+        let parameters = log_data.parameters();
+        assert_eq!(parameters.len(), 18);
+        assert!(!parameters.contains(&Parameter::Depth));
+        assert_eq!(
+            parameters
+                .iter()
+                .filter(|&&p| p == Parameter::Temperature)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn log_html_time_offset() {
+        let mut reader = Cursor::new(TEST_CONTENT.as_bytes());
+        let (_, log_data, _, _) = read_html(&mut reader, &ReadOptions::default()).unwrap();
+        assert_eq!(log_data.timezone, chrono::FixedOffset::east_opt(8 * 3600));
+        assert_eq!(
+            serde_json::to_value(&log_data).unwrap()[0]["DateTime"],
+            "2024-10-09T16:29:46+08:00"
+        );
+
+        let options = ReadOptions {
+            timezone: chrono::FixedOffset::east_opt(0),
+            ..Default::default()
+        };
+        let mut reader = Cursor::new(TEST_CONTENT.as_bytes());
+        let (_, log_data, _, _) = read_html(&mut reader, &options).unwrap();
+        assert_eq!(log_data.timezone, chrono::FixedOffset::east_opt(0));
+    }
+
+    #[test]
+    fn log_html_tick_timestamps() {
+        let content = TEST_CONTENT.replace(
+            r#"isi-timestamp="113276524167168""#,
+            r#"isi-timestamp="113276524199936""#,
+        );
+        let options = ReadOptions {
+            tick_timestamps: true,
+            ..Default::default()
+        };
+        let mut reader = Cursor::new(content.as_bytes());
+        let (_, log_data, _, _) = read_html(&mut reader, &options).unwrap();
+        let timestamps = log_data.timestamps();
+        assert_eq!(timestamps[0].to_string(), "2024-10-09 16:29:46");
+        assert_eq!(timestamps[1].to_string(), "2024-10-09 16:29:48.500");
+
+        assert_eq!(
+            tick_datetime(113276524036096, None).unwrap().to_string(),
+            "2024-10-09 08:29:46"
+        );
+    }
+
+    #[test]
+    fn log_html_data_quality() {
+        let mut reader = Cursor::new(TEST_CONTENT.as_bytes());
+        let (_, log_data, log_quality, _) =
+            read_html(&mut reader, &ReadOptions::default()).unwrap();
+
+        assert_eq!(log_quality.columns, log_data.columns);
+        assert_eq!(log_quality.rows.len(), log_data.num_rows());
+        let orp = log_data
+            .columns
+            .iter()
+            .position(|c| c == "ORP (mV)")
+            .unwrap();
+        assert_eq!(log_quality.rows[0][orp], Some(DataQuality(5)));
+        assert_eq!(log_quality.rows[0][1], Some(DataQuality(4)));
+        // DateTime and cells without `isi-data-quality`
+        assert_eq!(log_quality.rows[0][0], None);
+        assert_eq!(log_quality.rows[0][orp - 1], None);
+    }
+
+    #[test]
+    fn log_html_typed_attrs() {
+        let options = ReadOptions {
+            typed_attrs: true,
+            ..Default::default()
+        };
+        let mut reader = Cursor::new(TEST_CONTENT.as_bytes());
+        let (attr, _, _, _) = read_html(&mut reader, &options).unwrap();
+
+        let report = &attr["Report Properties"];
+        assert_eq!(report["Start Time"], "2024-10-09T16:29:44");
+        assert_eq!(report["Readings"], 1053);
+        assert_eq!(report["Time Offset"], "08:00:00");
+        assert_eq!(report["Time Offset (ms)"], 28800000);
+        assert_eq!(report["Duration (ms)"], 2106000);
+        assert_eq!(report["Start Time (UTC)"], "2024-10-09T08:29:44Z");
+        assert!(report.get("Readings (ms)").is_none());
+    }
+
+    #[test]
+    fn html_sensors() {
+        let mut reader = Cursor::new(TEST_CONTENT.as_bytes());
+        let sensors = read_html_sensors(&mut reader).unwrap();
+
+        let serials: Vec<_> = sensors.iter().map(|s| s.serial.unwrap()).collect();
+        assert_eq!(
+            serials,
+            vec![999997, 999995, 999991, 999998, 999996, 999999]
+        );
+        let ph = &sensors[2];
+        assert_eq!(ph.sensor_type, Some(58));
+        assert_eq!(ph.parameters, vec!["pH (pH)", "pH(mV) (mV)", "ORP (mV)"]);
+        assert_eq!(sensors[4].parameters.len(), 4);
+    }
+
+    #[test]
+    fn log_html_sensor_filter() {
+        let mut reader = Cursor::new(TEST_CONTENT.as_bytes());
+        let options = ReadOptions {
+            sensor_filter: Some(vec![999991]),
+            ..Default::default()
+        };
+        let (attr, log_data, _, _) = read_html(&mut reader, &options).unwrap();
+
+        assert_eq!(
+            log_data.columns,
+            vec!["DateTime", "pH (pH)", "pH(mV) (mV)", "ORP (mV)", "Marked"]
+        );
+        assert_eq!(log_data.num_rows(), 2);
+        assert!(matches!(&log_data.rows[0][1], CellValue::Float64(v) if *v == 6.4217362));
+        assert_eq!(attr["Log Data"]["Sensors"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn log_html_null_below_quality() {
+        let mut reader = Cursor::new(TEST_CONTENT.as_bytes());
+        let options = ReadOptions {
+            null_below_quality: Some(DataQuality(5)),
+            ..Default::default()
+        };
+        let (_, log_data, _, _) = read_html(&mut reader, &options).unwrap();
+
+        // Actual Conductivity, flagged with quality 4
+        assert!(matches!(log_data.rows[0][1], CellValue::Null));
+        // ORP, flagged with quality 5
+        assert!(matches!(log_data.rows[0][12], CellValue::Float64(v) if v == 123.78549));
+        // pH, without quality flag
+        assert!(matches!(log_data.rows[0][10], CellValue::Float64(v) if v == 6.4217362));
+    }
+
+    const SECTION_MEMBER_CONTENT: &str = r#"
+<html>
+    <body>
+        <table id="isi-report">
+        <tr class="sectionHeader"><td isi-group="ReportProperties">Report Properties</td></tr>
+        <tr class="sectionMember"><td isi-group-member="ReportProperties" isi-property="StartTime"><span isi-label="">Start Time</span>: <span isi-value="">2024-10-09 16:29:44</span></td></tr>
+        <tr class="sectionMember"><td isi-group-member="ReportProperties" isi-property="TimeOffset"><span isi-label="">Time Offset</span>: <span isi-value="">08:00:00</span></td></tr>
+        <tr class="sectionMember"><td isi-group-member="ReportProperties" isi-property="Readings"><span isi-label="">Readings</span> <span isi-value="">1053</span></td></tr>
+        <tr class="dataHeader" isi-data-table="">
+            <td isi-data-column-header="DateTime">Date Time</td>
+        </tr>
+        <tr class="data" isi-data-row=""><td class="dateTime">2024-10-09 16:29:46</td></tr>
+        </table>
+    </body>
+</html>
+    "#;
+
+    #[test]
+    fn log_html_section_member_separators() {
+        let mut reader = Cursor::new(SECTION_MEMBER_CONTENT.as_bytes());
+        let (attr, _, _, _) = read_html(&mut reader, &ReadOptions::default()).unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&attr).unwrap(),
+            serde_json::to_string(&json!({
+                "Report Properties": {
+                    "Start Time": "2024-10-09 16:29:44",
+                    "Time Offset": "08:00:00",
+                    "Readings": "1053"
+                }
+            }))
+            .unwrap()
+        );
+    }
+
+    fn zip_archive(files: &[(&str, &str)]) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(vec![]));
+        zip.add_directory("logs/", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        for (name, content) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn zipped_html_skips_non_html_entries() {
+        let archive = zip_archive(&[
+            ("logs/thumbnail.png", "not a log"),
+            ("logs/day_1.html", TEST_CONTENT),
+        ]);
+        let (_, log_data, _, _) =
+            read_zipped_html(Cursor::new(archive), &ReadOptions::default()).unwrap();
+        assert_eq!(log_data.num_rows(), 2);
+    }
+
+    #[test]
+    fn zipped_html_all() {
+        let archive = zip_archive(&[
+            ("logs/day_1.html", TEST_CONTENT),
+            ("logs/readme.txt", "not a log"),
+            ("logs/day_2.html", TH_HEADER_CONTENT),
+        ]);
+        let logs = read_zipped_html_all(Cursor::new(archive), &ReadOptions::default()).unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].1.num_rows(), 2);
+        assert_eq!(logs[0].2.rows.len(), 2);
+        assert_eq!(logs[1].1.num_rows(), 1);
+    }
+
+    const TH_HEADER_CONTENT: &str = r#"
+<html>
+    <body>
+        <table id="isi-report">
+        <tr class="dataHeader" isi-data-table="">
+            <th isi-data-column-header="DateTime">Date Time</th>
+            <th isi-data-column-header="Parameter" isi-device-serial-number="999996" isi-sensor-serial-number="999991" isi-sensor-type="58" isi-parameter-type="17" isi-unit-type="145">pH (pH) (999991)</th>
+            <th isi-data-column-header="Marked">Marked</th>
+        </tr>
+        <tr class="data" isi-data-row=""><td class="dateTime">2024-10-09 16:29:46</td><td>6.4217362</td><td></td></tr>
+        </table>
+    </body>
+</html>
+    "#;
+
+    #[test]
+    fn log_html_th_header() {
+        let mut reader = Cursor::new(TH_HEADER_CONTENT.as_bytes());
+        let (_, log_data, _, _) = read_html(&mut reader, &ReadOptions::default()).unwrap();
+
+        assert_eq!(log_data.columns, vec!["DateTime", "pH (pH)", "Marked"]);
+        assert_eq!(log_data.num_rows(), 1);
+        assert!(log_data.column_metadata[0].is_empty());
+        assert_eq!(log_data.column_metadata[1][SENSOR_SERIAL_KEY], "999991");
+        assert_eq!(log_data.column_metadata[1][SENSOR_TYPE_KEY], "58");
+        assert!(log_data.column_metadata[2].is_empty());
+        assert_eq!(
+            log_data.column_descriptors()[1].sensor_type,
+            Some(SensorType::PhOrp)
+        );
+    }
+
+    #[test]
+    fn log_html_short_row() {
+        let content = TH_HEADER_CONTENT.replace("<td></td></tr>", "</tr>");
+        let mut reader = Cursor::new(content.as_bytes());
+        assert!(matches!(
+            read_html(&mut reader, &ReadOptions::default()),
+            Err(AquaTrollLogError::ColumnCountMismatch {
+                line_number: 1,
+                expected: 3,
+                found: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn log_html_unknown_columns() {
+        let content = TH_HEADER_CONTENT
+            .replace(
+                r#"<th isi-data-column-header="Marked">Marked</th>"#,
+                r#"<th isi-data-column-header="Parameter">Foo</th><th isi-data-column-header="Parameter">Bar</th>"#,
+            )
+            .replace("<td></td></tr>", "<td>1</td><td>2</td></tr>");
+        let mut reader = Cursor::new(content.as_bytes());
+        let (_, log_data, _, _) = read_html(&mut reader, &ReadOptions::default()).unwrap();
+
+        assert_eq!(
+            log_data.columns,
+            vec!["DateTime", "pH (pH)", "Unknown", "Unknown_01"]
+        );
+    }
+
+    #[test]
+    fn log_html_malformed_parameter_type() {
+        let content =
+            TH_HEADER_CONTENT.replace(r#"isi-parameter-type="17""#, r#"isi-parameter-type="pH""#);
+        let mut reader = Cursor::new(content.as_bytes());
+        let (_, log_data, _, _) = read_html(&mut reader, &ReadOptions::default()).unwrap();
+
+        assert_eq!(log_data.columns, vec!["DateTime", "Unknown", "Marked"]);
+        assert!(matches!(log_data.rows[0][1], CellValue::Float64(v) if v == 6.4217362));
+
+        let document = Html::parse_fragment(
+            r#"<table><tr><td isi-parameter-type="pH" isi-unit-type=" 145 "></td></tr></table>"#,
+        );
+        let cell = document
+            .select(&Selector::parse("td").unwrap())
+            .next()
+            .unwrap();
+        let mut warnings = vec![];
+        assert_eq!(
+            parse_code::<u8>(cell, "isi-parameter-type", &mut warnings),
+            None
+        );
+        assert_eq!(
+            parse_code::<u16>(cell, "isi-unit-type", &mut warnings),
+            Some(145)
+        );
+        assert_eq!(
+            parse_code::<u32>(cell, "isi-sensor-type", &mut warnings),
+            None
+        );
+        assert_eq!(
+            warnings,
+            vec![ReadWarning::MalformedCode {
+                attribute: "isi-parameter-type".to_string(),
+                value: "pH".to_string()
+            }]
+        );
+    }
 }
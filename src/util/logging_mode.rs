@@ -0,0 +1,102 @@
+use serde_json::{Map, Value};
+
+/// Logging mode of a log, of the `Type` entry of `Log Configuration` (TXT logs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoggingMode {
+    /// Readings at a fixed sample rate
+    Linear,
+    /// Readings at a fixed sample rate, averaged over each interval
+    LinearAverage,
+    /// Readings at a fast sample rate, once or twice per second
+    FastLinear,
+    /// Readings at the default log rate, and at the sample rate while the event parameter is
+    /// beyond its triggers
+    Event,
+    /// Readings of intervals growing logarithmically, e.g. of pumping tests
+    Logarithmic,
+}
+
+impl LoggingMode {
+    /// Parse a name of the `Type` entry, e.g. `Linear` or `Event`
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "linear" => Some(LoggingMode::Linear),
+            "linear average" | "linear avg" | "average" => Some(LoggingMode::LinearAverage),
+            "fast linear" => Some(LoggingMode::FastLinear),
+            "event" => Some(LoggingMode::Event),
+            "logarithmic" | "log" | "true log" => Some(LoggingMode::Logarithmic),
+            _ => None,
+        }
+    }
+
+    /// Mode of the `Type` entry of the `Log Configuration` section, or derived from its
+    /// trigger and sample rate entries if the type is absent or unknown
+    pub fn from_attr(attr: &Map<String, Value>) -> Option<Self> {
+        let section = attr.get("Log Configuration").and_then(Value::as_object)?;
+        if let Some(mode) = section
+            .get("Type")
+            .and_then(Value::as_str)
+            .and_then(Self::from_name)
+        {
+            return Some(mode);
+        }
+
+        let has_entry = |keys: &[&str]| keys.iter().any(|key| section.contains_key(*key));
+        if has_entry(&[
+            "Event Sensor",
+            "Event Parameter",
+            "High Trigger",
+            "Low Trigger",
+        ]) {
+            Some(LoggingMode::Event)
+        } else if has_entry(&["Sample Rate"]) {
+            Some(LoggingMode::Linear)
+        } else {
+            None
+        }
+    }
+
+    /// Check if readings are taken at a fixed interval, so gaps between them are anomalous
+    /// rather than expected of the mode
+    pub fn is_fixed_interval(&self) -> bool {
+        matches!(
+            self,
+            LoggingMode::Linear | LoggingMode::LinearAverage | LoggingMode::FastLinear
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logging_mode_from_attr() {
+        let attr = serde_json::json!({
+            "Log Configuration": {
+                "Type": "Linear",
+                "Sample Rate": "Days: 0 hrs: 00 mins: 00 secs: 15"
+            }
+        });
+        let mode = LoggingMode::from_attr(attr.as_object().unwrap()).unwrap();
+        assert_eq!(mode, LoggingMode::Linear);
+        assert!(mode.is_fixed_interval());
+
+        let attr = serde_json::json!({
+            "Log Configuration": {
+                "Event Parameter": "pH (pH)",
+                "High Trigger": "0 (pH)"
+            }
+        });
+        let mode = LoggingMode::from_attr(attr.as_object().unwrap()).unwrap();
+        assert_eq!(mode, LoggingMode::Event);
+        assert!(!mode.is_fixed_interval());
+
+        let attr = serde_json::json!({"Log Configuration": {"Sample Rate": "Days: 0 hrs: 00 mins: 00 secs: 15"}});
+        assert_eq!(
+            LoggingMode::from_attr(attr.as_object().unwrap()),
+            Some(LoggingMode::Linear)
+        );
+        assert_eq!(LoggingMode::from_attr(&Map::new()), None);
+    }
+}
@@ -1,10 +1,37 @@
+#[cfg(feature = "arrow")]
+mod arrow;
+pub(crate) mod column;
 pub(crate) mod common;
+pub(crate) mod concat;
+pub(crate) mod conductivity;
 pub(crate) mod csv_reader;
 mod html_reader;
-mod param;
+pub(crate) mod logging_mode;
+pub(crate) mod param;
+#[cfg(feature = "polars")]
+mod polars;
+pub(crate) mod quality;
+pub(crate) mod sensor;
+#[cfg(all(feature = "flate2", feature = "tar"))]
+mod tar_reader;
 mod txt_reader;
-mod unit;
+pub(crate) mod unit;
+pub(crate) mod validate;
+pub(crate) mod warning;
 
-pub(crate) use csv_reader::read_table as read_csv_table;
-pub(crate) use html_reader::{read_html, read_zipped_html};
-pub(crate) use txt_reader::{read_attr, read_log_data_attr, read_table};
+#[cfg(feature = "polars")]
+pub(crate) use self::polars::table_to_data_frame;
+#[cfg(feature = "arrow")]
+pub(crate) use arrow::{flatten_attr, table_to_record_batch, unflatten_attr};
+pub use csv_reader::CsvReadOptions;
+pub(crate) use csv_reader::{read_csv_sensors, read_table as read_csv_table};
+pub(crate) use html_reader::{
+    read_html, read_html_sensors, read_zipped_html, read_zipped_html_all, read_zipped_html_sensors,
+};
+#[cfg(all(feature = "flate2", feature = "tar"))]
+pub(crate) use tar_reader::read_tar_gz_entries;
+pub(crate) use txt_reader::{
+    peek_content_line, read_attr, read_log_data_attr, read_table, read_table_layout,
+    read_txt_sensors, validate_txt,
+};
+pub use txt_reader::{TxtColumnSpan, TxtRows};
@@ -1,5 +1,10 @@
+use std::str::FromStr;
+
 use num_derive::FromPrimitive;
-use strum_macros::Display;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::error::AquaTrollLogError;
 
 // Paramaters
 // 1 Temperature
@@ -50,7 +55,9 @@ use strum_macros::Display;
 // 81 Crude Oil Fluorescence Intensity
 // 87 Colored Dissolved Organic Matter Concentration
 #[repr(u8)]
-#[derive(FromPrimitive, Display, Debug)]
+#[derive(
+    FromPrimitive, Display, EnumIter, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
 pub enum Parameter {
     Temperature = 1,
     Pressure = 2,
@@ -139,3 +146,191 @@ pub enum Parameter {
     #[strum(to_string = "CDOM")]
     ColoredDissolvedOrganicMatterConcentration = 87,
 }
+
+/// Names of parameters in TXT headers, and abbreviations of CSV headers of doubled units
+/// (e.g. `DO(%sat)` of `DO(%sat)(%Sat)`), other than their display names
+const PARAMETER_ALIASES: &[(&str, Parameter)] = &[
+    (
+        "Oxidation Reduction Potential (ORP)",
+        Parameter::OxidationReductionPotential,
+    ),
+    (
+        "Dissolved Oxygen (concentration)",
+        Parameter::DissolvedOxygenConcentration,
+    ),
+    (
+        "Dissolved Oxygen (%saturation)",
+        Parameter::DissolvedOxygenPercentSaturation,
+    ),
+    ("Partial Pressure Oxygen", Parameter::OxygenPartialPressure),
+    ("Water Density", Parameter::DensityOfWater),
+    ("Total Dissolved Solids", Parameter::TotalDissolvedSolids),
+    ("Battery Percentage", Parameter::BatteryCapacityRemaining),
+    ("DO(con)", Parameter::DissolvedOxygenConcentration),
+    ("DO(%sat)", Parameter::DissolvedOxygenPercentSaturation),
+];
+
+impl Parameter {
+    /// Compact ASCII token of the parameter, e.g. `RhodWT_FI` of
+    /// `Rhodamine WT Fluorescence Intensity`, for column names of
+    /// `AquaTrollLogReader::with_compact_column_names`
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Parameter::Temperature => "Temp",
+            Parameter::Pressure => "Pres",
+            Parameter::Depth => "Depth",
+            Parameter::DepthToWater => "DTW",
+            Parameter::SurfaceElevation => "SurfElev",
+            Parameter::ActualConductivity => "ActCond",
+            Parameter::SpecificConductivity => "SpCond",
+            Parameter::Resistivity => "Res",
+            Parameter::Salinity => "Sal",
+            Parameter::TotalDissolvedSolids => "TDS",
+            Parameter::DensityOfWater => "Dens",
+            Parameter::BarometricPressure => "Baro",
+            Parameter::PH => "pH",
+            Parameter::PHmV => "pH_mV",
+            Parameter::OxidationReductionPotential => "ORP",
+            Parameter::DissolvedOxygenConcentration => "DO",
+            Parameter::DissolvedOxygenPercentSaturation => "DO_Sat",
+            Parameter::Chloride => "Cl",
+            Parameter::Turbidity => "Turb",
+            Parameter::OxygenPartialPressure => "pO2",
+            Parameter::TotalSuspendedSolids => "TSS",
+            Parameter::ExternalVoltage => "ExtV",
+            Parameter::BatteryCapacityRemaining => "Batt",
+            Parameter::RhodamineWTConcentration => "RhodWT",
+            Parameter::RhodamineWTFluorescenceIntensity => "RhodWT_FI",
+            Parameter::ChlorideMV => "Cl_mV",
+            Parameter::NitrateAsNitrogenConcentration => "NO3_N",
+            Parameter::NitrateMV => "NO3_mV",
+            Parameter::AmmoniumAsNitrogenConcentration => "NH4_N",
+            Parameter::AmmoniumMV => "NH4_mV",
+            Parameter::AmmoniaAsNitrogenConcentration => "NH3_N",
+            Parameter::TotalAmmoniaAsNitrogenConcentration => "TotNH3_N",
+            Parameter::Eh => "Eh",
+            Parameter::Velocity => "Vel",
+            Parameter::ChlorophyllAConcentration => "ChlA",
+            Parameter::ChlorophyllAFluorescenceIntensity => "ChlA_FI",
+            Parameter::BlueGreenAlgaePhycocyaninConcentration => "PC",
+            Parameter::BlueGreenAlgaePhycocyaninFluorescenceIntensity => "PC_FI",
+            Parameter::BlueGreenAlgaePhycoerythrinConcentration => "PE",
+            Parameter::BlueGreenAlgaePhycoerythrinFluorescenceIntensity => "PE_FI",
+            Parameter::FluoresceinWTConcentration => "FlWT",
+            Parameter::FluoresceinWTFluorescenceIntensity => "FlWT_FI",
+            Parameter::FluorescentDissolvedOrganicMatterConcentration => "FDOM",
+            Parameter::FluorescentDissolvedOrganicMatterFluorescenceIntensity => "FDOM_FI",
+            Parameter::CrudeOilConcentration => "CrudeOil",
+            Parameter::CrudeOilFluorescenceIntensity => "CrudeOil_FI",
+            Parameter::ColoredDissolvedOrganicMatterConcentration => "CDOM",
+        }
+    }
+
+    /// Parameter of a display name or an alias of TXT and CSV headers
+    fn from_name(name: &str) -> Option<Self> {
+        Parameter::iter()
+            .find(|p| p.to_string() == name)
+            .or_else(|| {
+                PARAMETER_ALIASES
+                    .iter()
+                    .find(|(alias, _)| *alias == name)
+                    .map(|(_, p)| *p)
+            })
+    }
+}
+
+impl FromStr for Parameter {
+    type Err = AquaTrollLogError;
+
+    /// Parse parameter from its display name or a header alias, with or without a trailing
+    /// unit, e.g. `Actual Conductivity (µS/cm)`, `Oxidation Reduction Potential (ORP) (mV)`
+    /// and the doubled unit `DO(%sat)(%Sat)` of CSV headers
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let name = s.rsplit_once(" (").map(|(name, _)| name.trim_end());
+        // The unit group right after the parenthesized qualifier of the abbreviation
+        let abbreviation = s
+            .strip_suffix(')')
+            .and_then(|s| s.rsplit_once('('))
+            .map(|(abbreviation, _)| abbreviation)
+            .filter(|abbreviation| abbreviation.ends_with(')'));
+
+        Parameter::from_name(s)
+            .or_else(|| name.and_then(Parameter::from_name))
+            .or_else(|| abbreviation.and_then(Parameter::from_name))
+            .ok_or_else(|| AquaTrollLogError::UnknownParameter(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameter_from_str() {
+        assert!(matches!(
+            "Actual Conductivity (µS/cm)".parse(),
+            Ok(Parameter::ActualConductivity)
+        ));
+        assert!(matches!("pH (pH)".parse(), Ok(Parameter::PH)));
+        assert!(matches!("pH(mV) (mV)".parse(), Ok(Parameter::PHmV)));
+        assert!(matches!(
+            "pO₂ (Torr)".parse(),
+            Ok(Parameter::OxygenPartialPressure)
+        ));
+        assert!(matches!(
+            "NO₃⁻-N".parse(),
+            Ok(Parameter::NitrateAsNitrogenConcentration)
+        ));
+        assert!(matches!("Cl⁻ (mg/L)".parse(), Ok(Parameter::Chloride)));
+        assert!(matches!(
+            "DO % Saturation (DO % sat)".parse(),
+            Ok(Parameter::DissolvedOxygenPercentSaturation)
+        ));
+        assert!(matches!("pH(mV)".parse(), Ok(Parameter::PHmV)));
+        assert!(matches!(
+            "Oxidation Reduction Potential (ORP) (mV)".parse(),
+            Ok(Parameter::OxidationReductionPotential)
+        ));
+        assert!(matches!(
+            "Unknown (m)".parse::<Parameter>(),
+            Err(AquaTrollLogError::UnknownParameter(s)) if s == "Unknown (m)"
+        ));
+    }
+
+    #[test]
+    fn short_names() {
+        assert_eq!(
+            Parameter::RhodamineWTFluorescenceIntensity.short_name(),
+            "RhodWT_FI"
+        );
+        assert_eq!(Parameter::Eh.short_name(), "Eh");
+        let names: std::collections::HashSet<_> =
+            Parameter::iter().map(|p| p.short_name()).collect();
+        assert_eq!(names.len(), Parameter::iter().count());
+        assert!(names
+            .iter()
+            .all(|name| name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')));
+    }
+
+    #[test]
+    fn parameter_as_key() {
+        let set: std::collections::HashSet<Parameter> = [
+            Parameter::Temperature,
+            Parameter::PH,
+            Parameter::Temperature,
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Parameter::PH));
+
+        let map: std::collections::BTreeMap<Parameter, &str> = [
+            (Parameter::PH, "pH"),
+            (Parameter::Temperature, "Temperature"),
+        ]
+        .into();
+        assert_eq!(map.keys().next(), Some(&Parameter::Temperature));
+        assert_eq!(map[&Parameter::PH], "pH");
+    }
+}
@@ -0,0 +1,109 @@
+use polars::prelude::{Column, DataFrame, DataType, IntoColumn, NamedFrom, Series, TimeUnit};
+
+use super::common::{CellValue, Table};
+use crate::error::AquaTrollLogError;
+
+/// Values of the column at `index` of the type matched by `value`, nulls as `None`
+fn column_cells<'a, T>(
+    table: &'a Table,
+    index: usize,
+    value: impl Fn(&'a CellValue) -> Option<T>,
+) -> Result<Vec<Option<T>>, AquaTrollLogError> {
+    table
+        .column_values(index)
+        .map(|v| match (v, value(v)) {
+            (_, Some(v)) => Ok(Some(v)),
+            (CellValue::Null, None) => Ok(None),
+            _ => Err(AquaTrollLogError::InvalidData),
+        })
+        .collect()
+}
+
+/// Convert a table into a Polars data frame
+///
+/// Column types are taken from the first non-null cell of each column as of
+/// `table_to_record_batch`, timestamps are stored as `Datetime(Milliseconds, None)`.
+pub(crate) fn table_to_data_frame(table: &Table) -> Result<DataFrame, AquaTrollLogError> {
+    let mut columns: Vec<Column> = Vec::with_capacity(table.num_columns());
+
+    for (i, name) in table.columns.iter().enumerate() {
+        let name = name.as_str().into();
+        let first_value = table
+            .column_values(i)
+            .find(|v| !matches!(v, CellValue::Null));
+        let series = match first_value {
+            Some(CellValue::DateTime(_)) => Series::new(
+                name,
+                column_cells(table, i, |v| match v {
+                    CellValue::DateTime(dt) => Some(dt.and_utc().timestamp_millis()),
+                    _ => None,
+                })?,
+            )
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?,
+            Some(CellValue::Int64(_)) => Series::new(
+                name,
+                column_cells(table, i, |v| match v {
+                    CellValue::Int64(i) => Some(*i),
+                    _ => None,
+                })?,
+            ),
+            Some(CellValue::Boolean(_)) => Series::new(
+                name,
+                column_cells(table, i, |v| match v {
+                    CellValue::Boolean(b) => Some(*b),
+                    _ => None,
+                })?,
+            ),
+            Some(CellValue::Text(_)) => Series::new(
+                name,
+                column_cells(table, i, |v| match v {
+                    CellValue::Text(s) => Some(s.as_str()),
+                    _ => None,
+                })?,
+            ),
+            Some(CellValue::Float64(_)) | Some(CellValue::Null) | None => Series::new(
+                name,
+                column_cells(table, i, |v| match v {
+                    CellValue::Float64(f) => Some(*f),
+                    _ => None,
+                })?,
+            ),
+        };
+        columns.push(series.into_column());
+    }
+
+    Ok(DataFrame::new(table.num_rows(), columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::TableBuilder;
+    use super::*;
+
+    #[test]
+    fn data_frame_from_table() {
+        let table = TableBuilder::new()
+            .field_names(vec!["Date and Time".to_string(), "Temp(C)".to_string()])
+            .try_push_row(vec![
+                "2025/1/25 PM 05:15:06".to_string(),
+                "21.6".to_string(),
+            ])
+            .unwrap()
+            .try_push_row(vec!["2025/1/25 PM 05:15:36".to_string(), "".to_string()])
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let df = table_to_data_frame(&table).unwrap();
+        assert_eq!(df.get_column_names(), ["DateTime", "Temp(C)"]);
+        assert_eq!(
+            df.dtypes(),
+            [
+                DataType::Datetime(TimeUnit::Milliseconds, None),
+                DataType::Float64
+            ]
+        );
+        assert_eq!(df.height(), 2);
+        assert_eq!(df.column("Temp(C)").unwrap().null_count(), 1);
+    }
+}
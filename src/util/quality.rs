@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+/// Data quality code of a reading, as given by the `isi-data-quality` attribute of HTML logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DataQuality(pub u8);
+
+/// Data quality codes of the cells of a log data table, `None` for cells without a code
+///
+/// Columns and rows are aligned with the log data table.
+#[derive(Debug, Clone, Default)]
+pub struct QualityTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Option<DataQuality>>>,
+}
+
+impl QualityTable {
+    /// Quality codes of the column at `index`
+    pub fn column_values(&self, index: usize) -> impl Iterator<Item = Option<DataQuality>> + '_ {
+        self.rows.iter().map(move |row| row[index])
+    }
+
+    /// Keep only the rows at `indices`, in the given order
+    pub(crate) fn select_rows(&mut self, indices: &[usize]) {
+        self.rows = indices.iter().map(|&i| self.rows[i].clone()).collect();
+    }
+
+    /// Rename columns by the `renames` map of old to new names
+    pub(crate) fn rename_columns(&mut self, renames: &HashMap<String, String>) {
+        for name in self.columns.iter_mut() {
+            if let Some(new_name) = renames.get(name) {
+                *name = new_name.clone();
+            }
+        }
+    }
+
+    /// Keep only the columns flagged in `keep`, as of the columns kept of the log data table
+    pub(crate) fn retain_columns(&mut self, keep: &[bool]) {
+        let mut keep_iter = keep.iter();
+        self.columns.retain(|_| *keep_iter.next().unwrap());
+        for row in self.rows.iter_mut() {
+            let mut keep_iter = keep.iter();
+            row.retain(|_| *keep_iter.next().unwrap());
+        }
+    }
+}
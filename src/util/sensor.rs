@@ -0,0 +1,150 @@
+use std::str::FromStr;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::error::AquaTrollLogError;
+
+/// Type of a sensor module, by its model name in logs or its `isi-sensor-type` code of HTML
+/// logs
+#[repr(u8)]
+#[derive(FromPrimitive, Debug, Display, EnumIter, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SensorType {
+    #[strum(to_string = "pH/ORP")]
+    PhOrp = 58,
+    #[strum(to_string = "RDO")]
+    Rdo = 57,
+    Conductivity = 56,
+    Turbidity = 50,
+    Pressure = 54,
+    #[strum(to_string = "Barometric Pressure")]
+    Barometric = 59,
+    /// Sensors of the device itself, e.g. temperature, voltage and battery
+    Internal = 79,
+    /// Sensor of a type code not among the known ones
+    Unknown = 0,
+}
+
+impl SensorType {
+    /// Type of an `isi-sensor-type` code, `Unknown` for codes not among the known ones
+    pub fn from_code(code: u32) -> Self {
+        SensorType::from_u32(code).unwrap_or(SensorType::Unknown)
+    }
+}
+
+/// Spellings of sensor models in TXT logs which differ from the display names, as
+/// `(spelling, display name)`
+const SENSOR_TYPE_ALIASES: &[(&str, &str)] = &[
+    ("Rugged Dissolved Oxygen (RDO)", "RDO"),
+    ("Cond", "Conductivity"),
+    ("Turb", "Turbidity"),
+    ("Pres", "Pressure"),
+    ("Baro", "Barometric Pressure"),
+];
+
+impl FromStr for SensorType {
+    type Err = AquaTrollLogError;
+
+    /// Parse sensor type from its display name or its spelling in TXT logs, with or without a
+    /// trailing range, e.g. `Rugged Dissolved Oxygen (RDO)`, `Pressure (200m/650ft)` and
+    /// `Pres 650ft`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let candidates = [
+            Some(s),
+            s.split_once(" (").map(|(name, _)| name),
+            s.split_once(' ').map(|(name, _)| name),
+        ];
+
+        candidates
+            .into_iter()
+            .flatten()
+            .find_map(|name| {
+                let display = SENSOR_TYPE_ALIASES
+                    .iter()
+                    .find(|(alias, _)| *alias == name)
+                    .map_or(name, |(_, display)| display);
+                SensorType::iter()
+                    .filter(|t| *t != SensorType::Unknown)
+                    .find(|t| t.to_string() == display)
+            })
+            .ok_or_else(|| AquaTrollLogError::UnknownSensorType(s.to_string()))
+    }
+}
+
+/// A sensor of a log file and the parameters it measures
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SensorInfo {
+    /// Serial number of the sensor, unknown for CSV logs
+    pub serial: Option<u64>,
+    /// Serial number of the device of the sensor (HTML logs)
+    pub device_serial: Option<u64>,
+    /// Model of the sensor, e.g. `pH/ORP` (TXT logs)
+    pub model: Option<String>,
+    /// Sensor type code, e.g. `58` (HTML logs)
+    pub sensor_type: Option<u32>,
+    /// Names of the columns measured by the sensor
+    pub parameters: Vec<String>,
+}
+
+impl SensorInfo {
+    /// Type of the sensor parsed from its model if it's a known one, or of its sensor type
+    /// code
+    pub fn kind(&self) -> Option<SensorType> {
+        self.model
+            .as_deref()
+            .and_then(|model| model.parse().ok())
+            .or_else(|| self.sensor_type.map(SensorType::from_code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensor_type_from_str() {
+        assert_eq!(
+            "Rugged Dissolved Oxygen (RDO)"
+                .parse::<SensorType>()
+                .unwrap(),
+            SensorType::Rdo
+        );
+        assert_eq!("RDO".parse::<SensorType>().unwrap(), SensorType::Rdo);
+        assert_eq!("pH/ORP".parse::<SensorType>().unwrap(), SensorType::PhOrp);
+        assert_eq!(
+            "Cond".parse::<SensorType>().unwrap(),
+            "Conductivity".parse::<SensorType>().unwrap()
+        );
+        assert_eq!(
+            "Pressure (200m/650ft)".parse::<SensorType>().unwrap(),
+            SensorType::Pressure
+        );
+        assert_eq!(
+            "Pres 650ft".parse::<SensorType>().unwrap(),
+            SensorType::Pressure
+        );
+        assert!(matches!(
+            "Sonde".parse::<SensorType>(),
+            Err(AquaTrollLogError::UnknownSensorType(s)) if s == "Sonde"
+        ));
+        assert!("Unknown".parse::<SensorType>().is_err());
+    }
+
+    #[test]
+    fn sensor_type_from_code() {
+        assert_eq!(SensorType::from_code(56), SensorType::Conductivity);
+        assert_eq!(SensorType::from_code(57), SensorType::Rdo);
+        assert_eq!(SensorType::from_code(58), SensorType::PhOrp);
+        assert_eq!(SensorType::from_code(79), SensorType::Internal);
+        assert_eq!(SensorType::from_code(9999), SensorType::Unknown);
+
+        let sensor = SensorInfo {
+            sensor_type: Some(50),
+            ..Default::default()
+        };
+        assert_eq!(sensor.kind(), Some(SensorType::Turbidity));
+    }
+}
@@ -0,0 +1,52 @@
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+
+use crate::error::AquaTrollLogError;
+
+/// Read every regular file of a gzip compressed tar archive as `(path, content)` pairs
+pub(crate) fn read_tar_gz_entries<R: Read>(
+    reader: R,
+) -> Result<Vec<(String, Vec<u8>)>, AquaTrollLogError> {
+    let mut archive = tar::Archive::new(GzDecoder::new(reader));
+    let mut entries = vec![];
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path()?.to_string_lossy().to_string();
+        let mut buf = vec![];
+        entry.read_to_end(&mut buf)?;
+        entries.push((path, buf));
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    #[test]
+    fn tar_gz_entries() {
+        let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        let content = b"Date/Time,Temp(C)\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "logs/record.csv", &content[..])
+            .unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let entries = read_tar_gz_entries(archive.as_slice()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "logs/record.csv");
+        assert_eq!(entries[0].1, content);
+    }
+}
@@ -1,11 +1,20 @@
-use std::io::{BufRead, Seek, SeekFrom};
+use std::collections::HashMap;
+use std::io::{BufRead, Seek};
+
+use chrono::FixedOffset;
 
 use serde_json::{Map, Value};
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::error::AquaTrollLogError;
 
-use super::common::{DateTimeParser, Table, TableBuilder};
+use super::common::{
+    parse_time_zone, CellValue, ReadOptions, Row, Table, TableBuilder, DATETIME_COLUMN,
+    SENSOR_MODEL_KEY, SENSOR_SERIAL_KEY,
+};
+use super::sensor::SensorInfo;
+use super::validate::{unparseable_row, MonotonicTime, ValidationIssue};
+use super::warning::ReadWarning;
 
 #[derive(Debug)]
 enum LineContent<'a> {
@@ -30,6 +39,7 @@ pub(crate) fn read_attr<R: BufRead + Seek>(
     reader: &mut R,
     attr: &mut Map<String, Value>,
     is_root: bool,
+    options: &ReadOptions,
 ) -> Result<(), AquaTrollLogError> {
     let mut buf = String::new();
 
@@ -61,7 +71,7 @@ pub(crate) fn read_attr<R: BufRead + Seek>(
             LineContent::Header(k) => {
                 if is_root {
                     let mut new_block = Map::new();
-                    read_attr(reader, &mut new_block, false)?;
+                    read_attr(reader, &mut new_block, false, options)?;
                     attr.insert(k.to_string(), Value::Object(new_block));
                 } else {
                     reader.seek_relative(-(read_size as i64))?;
@@ -69,7 +79,7 @@ pub(crate) fn read_attr<R: BufRead + Seek>(
                 }
             }
             LineContent::Entry(k, v) => {
-                attr.insert(k.to_string(), Value::String(v.to_string()));
+                attr.insert(k.to_string(), options.attr_value(v));
             }
         }
     }
@@ -77,10 +87,14 @@ pub(crate) fn read_attr<R: BufRead + Seek>(
     Ok(())
 }
 
+/// Spans of columns as `(start, end)` grapheme positions
+type ColumnSpans = Vec<(usize, usize)>;
+
+/// Read header lines until the dash separator line, returns the header lines and column spans
 fn detect_column_span<R: BufRead>(
     reader: &mut R,
-) -> Result<(usize, Vec<(usize, usize)>), AquaTrollLogError> {
-    let mut line_offset = 0usize;
+) -> Result<(Vec<String>, ColumnSpans), AquaTrollLogError> {
+    let mut header_lines = vec![];
     let mut buf = String::new();
 
     loop {
@@ -91,23 +105,69 @@ fn detect_column_span<R: BufRead>(
 
         let buf_trim = buf.trim();
 
-        if buf_trim.is_empty() {
-            line_offset += 1;
-            continue;
-        }
-
         // Check if this is the separator line (dashes and spaces only)
-        if buf_trim.chars().all(|c| c == '-' || c.is_whitespace()) {
-            let spans = extract_dash_spans(buf_trim);
-            return Ok((line_offset, spans));
+        if !buf_trim.is_empty() && buf_trim.chars().all(|c| c == '-' || c.is_whitespace()) {
+            let mut spans = extract_dash_spans(buf_trim);
+            // The name and values of the last column may be wider than its dashes
+            if let Some(last) = spans.last_mut() {
+                last.1 = usize::MAX;
+            }
+            return Ok((header_lines, spans));
         }
 
-        line_offset += 1;
+        header_lines.push(buf.clone());
     }
 }
 
+/// Text of a column span in a line split into graphemes, trimmed
+fn span_text(graphemes: &[&str], (l, r): (usize, usize)) -> String {
+    let len = graphemes.len();
+    graphemes[usize::min(l, len)..usize::min(r.saturating_add(1), len)]
+        .concat()
+        .trim()
+        .to_string()
+}
+
+/// Names of columns, on the last header line before the separator
+fn column_names(header_lines: &[String], col_ranges: &[(usize, usize)]) -> Vec<String> {
+    let buf = header_lines.last().map(String::as_str).unwrap_or_default();
+    let buf_graphemes: Vec<&str> = buf.trim_end().graphemes(true).collect();
+    col_ranges
+        .iter()
+        .map(|&range| span_text(&buf_graphemes, range))
+        .collect()
+}
+
+/// Column of a fixed-width TXT table, as interpreted from the dash separator line
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxtColumnSpan {
+    /// Column name of the header
+    pub name: String,
+    /// Grapheme position of the first dash
+    pub start: usize,
+    /// Grapheme position after the last dash, `None` for the last column which extends to the
+    /// end of line
+    pub end: Option<usize>,
+}
+
+/// Read the header of a table, returns its columns and their spans
+pub(crate) fn read_table_layout<R: BufRead>(
+    reader: &mut R,
+) -> Result<Vec<TxtColumnSpan>, AquaTrollLogError> {
+    let (header_lines, col_ranges) = detect_column_span(reader)?;
+    Ok(column_names(&header_lines, &col_ranges)
+        .into_iter()
+        .zip(col_ranges)
+        .map(|(name, (start, end))| TxtColumnSpan {
+            name,
+            start,
+            end: (end != usize::MAX).then_some(end),
+        })
+        .collect())
+}
+
 /// Extract column spans from a dash-separator line (e.g., "----  ------  ---")
-fn extract_dash_spans(line: &str) -> Vec<(usize, usize)> {
+fn extract_dash_spans(line: &str) -> ColumnSpans {
     let mut spans = Vec::new();
     let mut start = None;
 
@@ -130,78 +190,406 @@ fn extract_dash_spans(line: &str) -> Vec<(usize, usize)> {
     spans
 }
 
-/// Parse table data of the log file
-pub(crate) fn read_table<R: BufRead + Seek>(
+/// Find values of columns from header lines labeled like `SN#: 999991` or `Sensor: pH/ORP`
+///
+/// Each value is assigned to the column whose span starts nearest to it, since the header
+/// text isn't always aligned to the dash separator.
+fn detect_column_labels(
+    lines: &[String],
+    col_ranges: &[(usize, usize)],
+    label: &str,
+) -> Vec<Option<String>> {
+    let mut values = vec![None; col_ranges.len()];
+
+    for line in lines {
+        let positions: Vec<usize> = line.match_indices(label).map(|(i, _)| i).collect();
+        for (n, &byte_pos) in positions.iter().enumerate() {
+            let pos = line[..byte_pos].graphemes(true).count();
+            let end = positions.get(n + 1).copied().unwrap_or(line.len());
+            let value = line[byte_pos + label.len()..end].trim();
+            let nearest = col_ranges
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (l, _))| l.abs_diff(pos))
+                .map(|(i, _)| i);
+            if let (Some(i), false) = (nearest, value.is_empty()) {
+                values[i] = Some(value.to_string());
+            }
+        }
+    }
+
+    values
+}
+
+/// Find sensor serial numbers of columns from the `SN#: ...` header line
+fn detect_column_serials(lines: &[String], col_ranges: &[(usize, usize)]) -> Vec<Option<u64>> {
+    detect_column_labels(lines, col_ranges, "SN#:")
+        .into_iter()
+        .map(|serial| serial.and_then(|s| s.parse().ok()))
+        .collect()
+}
+
+/// Read the header of a table, returns a table builder of the columns and their spans
+fn read_table_header<R: BufRead>(
     reader: &mut R,
-    datetime_parser: &DateTimeParser,
-) -> Result<Table, AquaTrollLogError> {
-    let mut buf = String::new();
+    options: &ReadOptions,
+) -> Result<(TableBuilder, ColumnSpans), AquaTrollLogError> {
+    let (header_lines, col_ranges) = detect_column_span(reader)?;
+    let names = column_names(&header_lines, &col_ranges);
 
-    let start_pos = reader.stream_position()?; // Get current position of reader
-    let (line_offset, col_ranges) = detect_column_span(reader)?;
+    let serials = detect_column_serials(&header_lines, &col_ranges);
+    let models = detect_column_labels(&header_lines, &col_ranges, "Sensor:");
 
-    // Seek to line contains column names
-    reader.seek(SeekFrom::Start(start_pos))?;
-    for _ in 0..line_offset {
-        buf.clear();
-        reader.read_line(&mut buf)?;
-    }
+    // Drop columns of sensors not to be read
+    let (col_ranges, (fields, column_metadata)): (Vec<(usize, usize)>, (Vec<_>, Vec<_>)) =
+        col_ranges
+            .iter()
+            .zip(names)
+            .zip(serials)
+            .zip(models)
+            .filter(|((_, serial), _)| options.accepts_sensor(*serial))
+            .map(|(((range, name), serial), model)| {
+                let mut metadata = HashMap::new();
+                if let Some(serial) = serial {
+                    metadata.insert(SENSOR_SERIAL_KEY.to_string(), serial.to_string());
+                }
+                if let Some(model) = model {
+                    metadata.insert(SENSOR_MODEL_KEY.to_string(), model);
+                }
+                let name = match serial {
+                    Some(serial) if options.serial_column_names => format!("{name} ({serial})"),
+                    _ => name,
+                };
+                (*range, (name, metadata))
+            })
+            .unzip();
 
-    let fields = col_ranges
-        .iter()
-        .map(|range| {
-            buf[range.0..usize::min(range.1 + 1, buf.trim().len())]
-                .trim()
-                .to_string()
-        })
-        .collect();
-    let mut table_builder = TableBuilder::new()
+    let table_builder = TableBuilder::new()
         .field_names(fields)
-        .with_datetime_parser(datetime_parser.clone());
-
-    let mut buf = String::new();
-    reader.read_line(&mut buf)?;
+        .field_types(&options.column_types())
+        .with_column_metadata(column_metadata)
+        .with_datetime_parser(options.datetime_parser.clone())
+        .with_timezone(options.timezone)
+        .with_infinite_as_null(options.infinite_as_null)
+        .with_datetime_column_name(options.datetime_column_name.clone());
+
+    Ok((table_builder, col_ranges))
+}
 
-    loop {
-        buf.clear();
-        let read_size = reader.read_line(&mut buf)?;
+/// Longest prefix of a line tried as a merged timestamp
+const MAX_DATETIME_LEN: usize = 40;
+
+/// Reader of fixed-width rows of a table
+struct RowReader {
+    col_ranges: ColumnSpans,
+    /// Number of lines read since the table header
+    line_number: usize,
+    /// Last line read
+    line: String,
+    lenient: bool,
+    recovery: bool,
+    /// Warnings of short and recovered rows
+    warnings: Vec<ReadWarning>,
+}
 
-        // End of file
-        if read_size == 0 {
-            break;
+impl RowReader {
+    fn new(col_ranges: ColumnSpans, options: &ReadOptions) -> Self {
+        Self {
+            col_ranges,
+            line_number: 0,
+            line: String::new(),
+            lenient: options.lenient_columns,
+            recovery: options.recovery,
+            warnings: vec![],
         }
+    }
 
-        let buf_trim = buf.trim();
+    /// Parse a row read, rows of the timestamp merged with the first reading are split if
+    /// recovery is enabled
+    fn parse(
+        &mut self,
+        table_builder: &TableBuilder,
+        row: Vec<String>,
+    ) -> Result<Vec<CellValue>, AquaTrollLogError> {
+        match table_builder.parse_row(row) {
+            Err(e) if self.recovery => match self.split_merged_datetime(table_builder) {
+                Some(row) => {
+                    self.warnings.push(ReadWarning::RecoveredRow {
+                        line_number: self.line_number,
+                    });
+                    table_builder.parse_row(row)
+                }
+                None => Err(e),
+            },
+            row => row,
+        }
+    }
 
-        // Empty line
-        if buf_trim.is_empty() {
-            continue;
+    /// Split the last line into the longest prefix parsing as a timestamp and the readings
+    /// separated by whitespace, if they fill the other columns
+    fn split_merged_datetime(&self, table_builder: &TableBuilder) -> Option<Vec<String>> {
+        if table_builder.columns().first().map(String::as_str) != Some(DATETIME_COLUMN) {
+            return None;
         }
+        let line = self.line.trim();
+
+        line.char_indices()
+            .map(|(i, _)| i)
+            .skip(1)
+            .chain([line.len()])
+            .take_while(|&i| i <= MAX_DATETIME_LEN)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .find_map(|i| {
+                let (datetime, rest) = line.split_at(i);
+                table_builder.parse_datetime(datetime).ok()?;
+                let values: Vec<&str> = rest.split_whitespace().collect();
+                (values.len() + 1 == self.col_ranges.len()).then(|| {
+                    std::iter::once(datetime.trim())
+                        .chain(values)
+                        .map(str::to_string)
+                        .collect()
+                })
+            })
+    }
 
-        // Section break
-        if buf_trim.chars().all(|c| c == '_') {
-            break;
+    /// Read the next row, rows not spanning all columns are an error unless lenient
+    fn read<R: BufRead>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<Option<Vec<String>>, AquaTrollLogError> {
+        loop {
+            self.line.clear();
+            let read_size = reader.read_line(&mut self.line)?;
+            self.line_number += 1;
+
+            // End of file
+            if read_size == 0 {
+                return Ok(None);
+            }
+
+            let buf_trim = self.line.trim();
+
+            // Empty line
+            if buf_trim.is_empty() {
+                continue;
+            }
+
+            // Section break
+            if buf_trim.chars().all(|c| c == '_') {
+                return Ok(None);
+            }
+
+            // Trailing whitespace is kept to count blank cells of the last columns, a single
+            // `grapheme` may compose with multiple code points
+            let buf_graphemes: Vec<&str> = self
+                .line
+                .trim_start()
+                .trim_end_matches(['\r', '\n'])
+                .graphemes(true)
+                .collect();
+            let buf_len = buf_graphemes.len();
+
+            let found = self
+                .col_ranges
+                .iter()
+                .filter(|&&(l, _)| l < buf_len)
+                .count();
+            if found < self.col_ranges.len() && !self.lenient {
+                return Err(AquaTrollLogError::ColumnCountMismatch {
+                    line_number: self.line_number,
+                    expected: self.col_ranges.len(),
+                    found,
+                });
+            } else if found < self.col_ranges.len() {
+                self.warnings.push(ReadWarning::ShortRow {
+                    line_number: self.line_number,
+                    expected: self.col_ranges.len(),
+                    found,
+                });
+            }
+
+            let row = self
+                .col_ranges
+                .iter()
+                .map(|&range| span_text(&buf_graphemes, range))
+                .collect();
+            return Ok(Some(row));
         }
+    }
+}
 
-        // A single `grapheme` may compose with multiple code points
-        let buf_graphemes: Vec<&str> = buf_trim.graphemes(true).collect();
-        let buf_len = buf_graphemes.len();
+/// Parse table data of the log file, warnings of short and recovered rows are appended to
+/// `warnings`
+pub(crate) fn read_table<R: BufRead>(
+    reader: &mut R,
+    options: &ReadOptions,
+    warnings: &mut Vec<ReadWarning>,
+) -> Result<Table, AquaTrollLogError> {
+    let (mut table_builder, col_ranges) = read_table_header(reader, options)?;
+    let mut row_reader = RowReader::new(col_ranges, options);
 
-        let row = col_ranges
-            .iter()
-            .map(|&(l, r)| {
-                buf_graphemes[l..usize::min(r + 1, buf_len)]
-                    .concat()
-                    .trim()
-                    .to_string()
-            })
-            .collect();
-        table_builder = table_builder.try_push_row(row)?;
+    while let Some(row) = row_reader.read(reader)? {
+        let row = row_reader.parse(&table_builder, row)?;
+        table_builder = table_builder.push_parsed_row(row)?;
     }
 
+    warnings.append(&mut row_reader.warnings);
     table_builder.try_build()
 }
 
+/// Check the log data table of the log file, rows failing to parse are reported as issues
+pub(crate) fn validate_txt<R: BufRead>(
+    reader: &mut R,
+    options: &ReadOptions,
+) -> Result<Vec<ValidationIssue>, AquaTrollLogError> {
+    let attr = read_log_data_attr(reader)?;
+    let (table_builder, col_ranges) = read_table_header(reader, options)?;
+    let mut row_reader = RowReader::new(col_ranges, options);
+    let mut issues = vec![];
+    let mut monotonic_time = MonotonicTime::default();
+    let mut n_rows = 0;
+
+    loop {
+        let row = match row_reader.read(reader) {
+            Ok(Some(row)) => row_reader.parse(&table_builder, row).map(Row::new),
+            Ok(None) => break,
+            Err(e) => Err(e),
+        };
+        match row {
+            Ok(row) => issues.extend(monotonic_time.check(n_rows, row.datetime)),
+            Err(e) => issues.push(unparseable_row(&e, Some(row_reader.line_number)).ok_or(e)?),
+        }
+        n_rows += 1;
+    }
+
+    if let Some(expected) = attr.get("Record Count").and_then(Value::as_u64) {
+        if expected as usize != n_rows {
+            issues.push(ValidationIssue::RecordCountMismatch {
+                expected: expected as usize,
+                found: n_rows,
+            });
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Read sensors of the `Log Data` section and the columns they measure, rows of the table are
+/// not read
+pub(crate) fn read_txt_sensors<R: BufRead>(
+    reader: &mut R,
+) -> Result<Vec<SensorInfo>, AquaTrollLogError> {
+    let attr = read_log_data_attr(reader)?;
+    let (table_builder, _) = read_table_header(reader, &ReadOptions::default())?;
+
+    let sensors = attr
+        .get("Sensors")
+        .and_then(Value::as_array)
+        .ok_or(AquaTrollLogError::InvalidData)?;
+    Ok(sensors
+        .iter()
+        .map(|sensor| {
+            let serial = sensor
+                .get("Serial")
+                .and_then(Value::as_str)
+                .and_then(|s| s.parse().ok());
+            let parameters = table_builder
+                .columns()
+                .iter()
+                .zip(table_builder.column_metadata())
+                .filter(|(_, metadata)| {
+                    serial.is_some()
+                        && metadata.get(SENSOR_SERIAL_KEY).and_then(|s| s.parse().ok()) == serial
+                })
+                .map(|(name, _)| name.clone())
+                .collect();
+            SensorInfo {
+                serial,
+                device_serial: None,
+                model: sensor
+                    .get("Sensor")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                sensor_type: None,
+                parameters,
+            }
+        })
+        .collect())
+}
+
+/// Rows of the log data table of a TXT log file, read one line at a time
+///
+/// Rows are parsed as they're consumed and not collected into a table.
+pub struct TxtRows<R> {
+    reader: R,
+    table_builder: TableBuilder,
+    row_reader: RowReader,
+    attr: Map<String, Value>,
+    done: bool,
+}
+
+impl<R: BufRead> TxtRows<R> {
+    /// Skip to the log data table of a TXT log file and read its header
+    pub(crate) fn new(mut reader: R, options: &ReadOptions) -> Result<Self, AquaTrollLogError> {
+        let attr = read_log_data_attr(&mut reader)?;
+        let timezone = options.timezone.or_else(|| {
+            attr.get("Time Zone")
+                .and_then(Value::as_str)
+                .and_then(parse_time_zone)
+        });
+        let (table_builder, col_ranges) = read_table_header(&mut reader, options)?;
+
+        Ok(Self {
+            reader,
+            table_builder: table_builder.with_timezone(timezone),
+            row_reader: RowReader::new(col_ranges, options),
+            attr,
+            done: false,
+        })
+    }
+
+    /// Column names of the rows
+    pub fn columns(&self) -> &[String] {
+        self.table_builder.columns()
+    }
+
+    /// Attributes of the `Log Data` section, e.g. `Record Count` and `Sensors`
+    pub fn attr(&self) -> &Map<String, Value> {
+        &self.attr
+    }
+
+    /// Time zone of the timestamps, if known
+    pub fn timezone(&self) -> Option<FixedOffset> {
+        self.table_builder.timezone()
+    }
+}
+
+impl<R: BufRead> Iterator for TxtRows<R> {
+    type Item = Result<Row, AquaTrollLogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let row = self
+            .row_reader
+            .read(&mut self.reader)
+            .and_then(|row| {
+                row.map(|row| self.row_reader.parse(&self.table_builder, row))
+                    .transpose()
+            })
+            .transpose();
+        // Stop after the end of table or the first error
+        if !matches!(row, Some(Ok(_))) {
+            self.done = true;
+        }
+        row.map(|row| row.map(Row::new))
+    }
+}
+
 fn read_entry<'a>(buf: &'a str, expected_key: &str) -> Result<&'a str, AquaTrollLogError> {
     match parse_line_content(buf) {
         LineContent::Entry(key, value) if key == expected_key => Ok(value),
@@ -213,7 +601,28 @@ fn read_entry<'a>(buf: &'a str, expected_key: &str) -> Result<&'a str, AquaTroll
     }
 }
 
-pub(crate) fn read_log_data_attr<R: BufRead + Seek>(
+/// Skip empty lines and section breaks, returns the next line of content without consuming it
+pub(crate) fn peek_content_line<R: BufRead + Seek>(
+    reader: &mut R,
+) -> Result<Option<String>, AquaTrollLogError> {
+    let mut buf = String::new();
+
+    loop {
+        buf.clear();
+        let read_size = reader.read_line(&mut buf)?;
+        if read_size == 0 {
+            return Ok(None);
+        }
+
+        let buf_trim = buf.trim();
+        if !buf_trim.is_empty() && !buf_trim.chars().all(|c| c == '_') {
+            reader.seek_relative(-(read_size as i64))?;
+            return Ok(Some(buf_trim.to_string()));
+        }
+    }
+}
+
+pub(crate) fn read_log_data_attr<R: BufRead>(
     reader: &mut R,
 ) -> Result<Map<String, Value>, AquaTrollLogError> {
     let mut buf = String::new();
@@ -301,6 +710,9 @@ mod tests {
 
     use serde_json::{json, Number};
 
+    use super::super::param::Parameter;
+    use super::super::sensor::SensorType;
+    use super::super::unit::Unit;
     use super::*;
 
     static ATTR_TXT: &str = r#"
@@ -339,7 +751,7 @@ ________________________________________________________________________________
         let mut buf = Cursor::new(ATTR_TXT.as_bytes());
         let mut attr = Map::new();
 
-        read_attr(&mut buf, &mut attr, true).unwrap();
+        read_attr(&mut buf, &mut attr, true, &ReadOptions::default()).unwrap();
 
         assert_eq!(
             serde_json::to_string(&attr).unwrap(),
@@ -371,6 +783,24 @@ ________________________________________________________________________________
         );
     }
 
+    #[test]
+    fn typed_attr_parser() {
+        let mut buf = Cursor::new(ATTR_TXT.as_bytes());
+        let mut attr = Map::new();
+        let options = ReadOptions {
+            typed_attrs: true,
+            ..Default::default()
+        };
+
+        read_attr(&mut buf, &mut attr, true, &options).unwrap();
+
+        assert_eq!(attr["Report Date"], "2025-01-02T12:23:23");
+        assert_eq!(attr["Device Properties"]["Serial Number"], 999996);
+        assert_eq!(attr["Device Properties"]["Site"], "Sample Site");
+        assert_eq!(attr["Other Log Settings"]["TDS Factor"], 0.65);
+        assert_eq!(attr["Other Log Settings"]["Temperature"], "21.4429 (C)");
+    }
+
     static LOG_NOTE_TXT: &str = r#"
 Log Notes:
 Date and Time              Note
@@ -384,13 +814,95 @@ ________________________________________________________________________________
     #[test]
     fn log_note_parser() {
         let mut buf = Cursor::new(LOG_NOTE_TXT.as_bytes());
-        let notes = read_table(&mut buf, &DateTimeParser::Default).unwrap();
+        let notes = read_table(&mut buf, &ReadOptions::default(), &mut vec![]).unwrap();
         assert_eq!(notes.num_columns(), 2);
         assert_eq!(notes.num_rows(), 3);
         assert_eq!(notes.column_name(0), "DateTime");
         assert_eq!(notes.column_name(1), "Note");
     }
 
+    #[test]
+    fn log_note_column_count_mismatch() {
+        let truncated = LOG_NOTE_TXT.replace(
+            "2025/1/30 AM 07:16:58      Used Battery: 66% Used Memory: 29%   User Name: USER",
+            "2025/1/30 AM 07:16:58",
+        );
+
+        let mut buf = Cursor::new(truncated.as_bytes());
+        assert!(matches!(
+            read_table(&mut buf, &ReadOptions::default(), &mut vec![]),
+            Err(AquaTrollLogError::ColumnCountMismatch {
+                line_number: 2,
+                expected: 2,
+                found: 1
+            })
+        ));
+
+        let mut buf = Cursor::new(truncated.as_bytes());
+        let options = ReadOptions {
+            lenient_columns: true,
+            ..Default::default()
+        };
+        let mut warnings = vec![];
+        let notes = read_table(&mut buf, &options, &mut warnings).unwrap();
+        assert_eq!(notes.num_rows(), 3);
+        assert!(matches!(&notes.rows[1][1], CellValue::Text(s) if s.is_empty()));
+        assert_eq!(
+            warnings,
+            vec![ReadWarning::ShortRow {
+                line_number: 2,
+                expected: 2,
+                found: 1
+            }]
+        );
+    }
+
+    static NARROW_LAST_COLUMN_TXT: &str = "
+Date and Time              Actual Conductivity (µS/cm)     Depth (m)
+----------------------     ---------------------------     -----
+2025/1/30 PM 05:00:59                          271.551     10.317   
+2025/1/30 PM 05:01:14                          271.552      9.8     
+";
+
+    #[test]
+    fn table_layout() {
+        let mut buf = Cursor::new(NARROW_LAST_COLUMN_TXT.as_bytes());
+        let layout = read_table_layout(&mut buf).unwrap();
+        assert_eq!(
+            layout,
+            vec![
+                TxtColumnSpan {
+                    name: "Date and Time".to_string(),
+                    start: 0,
+                    end: Some(22),
+                },
+                TxtColumnSpan {
+                    name: "Actual Conductivity (µS/cm)".to_string(),
+                    start: 27,
+                    end: Some(54),
+                },
+                TxtColumnSpan {
+                    name: "Depth (m)".to_string(),
+                    start: 59,
+                    end: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn narrow_last_column() {
+        let mut buf = Cursor::new(NARROW_LAST_COLUMN_TXT.as_bytes());
+        let table = read_table(&mut buf, &ReadOptions::default(), &mut vec![]).unwrap();
+        assert_eq!(
+            table.columns,
+            vec!["DateTime", "Actual Conductivity (µS/cm)", "Depth (m)"]
+        );
+        assert!(matches!(table.rows[0][1], CellValue::Float64(v) if v == 271.551));
+        assert!(matches!(table.rows[0][2], CellValue::Float64(v) if v == 10.317));
+        assert!(matches!(table.rows[1][2], CellValue::Float64(v) if v == 9.8));
+    }
+
     static LOG_DATA_TXT: &str = r#"
 Log Data:
 Record Count: 2
@@ -429,10 +941,184 @@ Date and Time              Seconds          pH (pH)
     #[test]
     fn log_data_table() {
         let mut buf = Cursor::new(LOG_DATA_TXT.as_bytes());
-        let data_table = read_table(&mut buf, &DateTimeParser::Default).unwrap();
+        let data_table = read_table(&mut buf, &ReadOptions::default(), &mut vec![]).unwrap();
 
         assert_eq!(data_table.num_columns(), 22);
         assert_eq!(data_table.column_name(0), "DateTime");
         assert_eq!(data_table.column_name(2), "pH (pH)");
     }
+
+    #[test]
+    fn validate_log_data() {
+        let mut buf = Cursor::new(LOG_DATA_TXT.as_bytes());
+        assert_eq!(
+            validate_txt(&mut buf, &ReadOptions::default()).unwrap(),
+            vec![]
+        );
+
+        let first_row = LOG_DATA_TXT
+            .lines()
+            .find(|l| l.starts_with("2025/1/30 PM 05:00:59"))
+            .unwrap();
+        let content = format!(
+            "{}\n{}\n2025/1/30 PM 05:01:29            30.000\n",
+            LOG_DATA_TXT
+                .replace("Record Count: 2", "Record Count: 5")
+                .replace("15.000", "xx.xxx")
+                .trim_end(),
+            first_row
+        );
+        let mut buf = Cursor::new(content.as_bytes());
+        let issues = validate_txt(&mut buf, &ReadOptions::default()).unwrap();
+
+        assert_eq!(issues.len(), 4, "{issues:?}");
+        assert!(matches!(
+            &issues[0],
+            ValidationIssue::UnparseableRow { line_number: Some(_), message }
+                if message.contains("float")
+        ));
+        assert!(matches!(
+            issues[1],
+            ValidationIssue::NonMonotonicTime { row: 2, .. }
+        ));
+        assert!(matches!(
+            &issues[2],
+            ValidationIssue::UnparseableRow { message, .. } if message.contains("expected 22 columns")
+        ));
+        assert_eq!(
+            issues[3],
+            ValidationIssue::RecordCountMismatch {
+                expected: 5,
+                found: 4
+            }
+        );
+    }
+
+    #[test]
+    fn log_data_blank_last_cell() {
+        let first_row = LOG_DATA_TXT
+            .lines()
+            .find(|l| l.starts_with("2025/1/30 PM 05:00:59"))
+            .unwrap();
+        let (values, last) = first_row.trim_end().rsplit_once(' ').unwrap();
+        let blank_row = format!("{values} {}", " ".repeat(last.len()));
+        let content = LOG_DATA_TXT.replace(first_row, &blank_row);
+
+        let mut buf = Cursor::new(content.as_bytes());
+        let mut warnings = vec![];
+        let data_table = read_table(&mut buf, &ReadOptions::default(), &mut warnings).unwrap();
+        assert_eq!(data_table.num_rows(), 2);
+        assert!(matches!(data_table.rows[0][21], CellValue::Null));
+        assert!(matches!(data_table.rows[1][21], CellValue::Float64(_)));
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn log_data_merged_datetime() {
+        let content = LOG_DATA_TXT.replace(
+            "2025/1/30 PM 05:01:14            15.000",
+            "2025/1/30 PM 05:01:141234567890015.000",
+        );
+        let mut buf = Cursor::new(content.as_bytes());
+        assert!(read_table(&mut buf, &ReadOptions::default(), &mut vec![]).is_err());
+
+        let options = ReadOptions {
+            recovery: true,
+            ..Default::default()
+        };
+        let mut buf = Cursor::new(content.as_bytes());
+        let mut warnings = vec![];
+        let data_table = read_table(&mut buf, &options, &mut warnings).unwrap();
+        assert_eq!(data_table.num_rows(), 2);
+        assert!(matches!(warnings[..], [ReadWarning::RecoveredRow { .. }]));
+        assert_eq!(
+            data_table.timestamps()[1].to_string(),
+            "2025-01-30 17:01:14"
+        );
+        assert!(matches!(data_table.rows[1][1], CellValue::Float64(v) if v == 1234567890015.0));
+        assert!(matches!(data_table.rows[1][2], CellValue::Float64(v) if v == 7.736));
+        assert!(matches!(data_table.rows[1][21], CellValue::Float64(v) if v == 10.317));
+    }
+
+    #[test]
+    fn log_data_table_sensor_filter() {
+        let mut buf = Cursor::new(LOG_DATA_TXT.as_bytes());
+        let options = ReadOptions {
+            sensor_filter: Some(vec![999999]),
+            ..Default::default()
+        };
+        let data_table = read_table(&mut buf, &options, &mut vec![]).unwrap();
+
+        assert_eq!(
+            data_table.columns,
+            vec!["DateTime", "Seconds", "Turbidity (NTU)"]
+        );
+        assert_eq!(data_table.num_rows(), 2);
+        assert_eq!(data_table.column_metadata[2][SENSOR_SERIAL_KEY], "999999");
+    }
+
+    #[test]
+    fn txt_sensors() {
+        let mut buf = Cursor::new(LOG_DATA_TXT.as_bytes());
+        let sensors = read_txt_sensors(&mut buf).unwrap();
+
+        assert_eq!(sensors.len(), 6);
+        assert_eq!(sensors[0].serial, Some(999991));
+        assert_eq!(sensors[0].model.as_deref(), Some("pH/ORP"));
+        assert_eq!(sensors[0].parameters.len(), 3);
+        assert_eq!(sensors[0].parameters[0], "pH (pH)");
+        assert_eq!(sensors[3].parameters, vec!["Turbidity (NTU)"]);
+
+        // The sensor list and the data header spell models differently
+        let mut buf = Cursor::new(LOG_DATA_TXT.as_bytes());
+        let descriptors = read_table(&mut buf, &ReadOptions::default(), &mut vec![])
+            .unwrap()
+            .column_descriptors();
+        let rdo = descriptors
+            .iter()
+            .find(|d| d.sensor_serial == sensors[1].serial)
+            .unwrap();
+        assert_eq!(sensors[1].kind(), Some(SensorType::Rdo));
+        assert_eq!(rdo.sensor_type, sensors[1].kind());
+    }
+
+    #[test]
+    fn log_data_column_metadata() {
+        let mut buf = Cursor::new(LOG_DATA_TXT.as_bytes());
+        let data_table = read_table(&mut buf, &ReadOptions::default(), &mut vec![]).unwrap();
+
+        assert_eq!(data_table.column_metadata.len(), data_table.num_columns());
+        assert!(data_table.column_metadata[0].is_empty());
+        assert!(data_table.column_metadata[1].is_empty());
+        assert_eq!(data_table.column_name(2), "pH (pH)");
+        assert_eq!(data_table.column_metadata[2][SENSOR_SERIAL_KEY], "999991");
+        assert_eq!(data_table.column_metadata[2][SENSOR_MODEL_KEY], "pH/ORP");
+        let depth = data_table.num_columns() - 1;
+        assert_eq!(
+            data_table.column_metadata[depth][SENSOR_SERIAL_KEY],
+            "999998"
+        );
+        assert_eq!(
+            data_table.column_metadata[depth][SENSOR_MODEL_KEY],
+            "Pres 650ft"
+        );
+    }
+
+    #[test]
+    fn serial_column_names() {
+        let options = ReadOptions {
+            serial_column_names: true,
+            ..Default::default()
+        };
+        let mut buf = Cursor::new(LOG_DATA_TXT.as_bytes());
+        let data_table = read_table(&mut buf, &options, &mut vec![]).unwrap();
+
+        assert_eq!(data_table.column_name(0), "DateTime");
+        assert_eq!(data_table.column_name(1), "Seconds");
+        assert_eq!(data_table.column_name(2), "pH (pH) (999991)");
+        let descriptor = &data_table.column_descriptors()[2];
+        assert!(matches!(descriptor.parameter, Some(Parameter::PH)));
+        assert!(matches!(descriptor.unit, Some(Unit::PH)));
+        assert!(data_table.unknown_units().is_empty());
+    }
 }
@@ -1,5 +1,10 @@
+use std::str::FromStr;
+
 use num_derive::FromPrimitive;
-use strum_macros::Display;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::error::AquaTrollLogError;
 
 // # Temperature
 // 1 C Celsius
@@ -123,7 +128,9 @@ use strum_macros::Display;
 // # Velocity
 // 305 ft/s Feet per second
 // 306 m/s Meters per second
-#[derive(FromPrimitive, Display, Debug)]
+#[derive(
+    FromPrimitive, Display, EnumIter, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
 #[repr(u16)]
 pub enum Unit {
     #[strum(to_string = "°C")]
@@ -278,3 +285,412 @@ pub enum Unit {
     #[strum(to_string = "m/s")]
     MetersPerSecond = 306,
 }
+
+/// Physical quantity measured in a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    Temperature,
+    Pressure,
+    Length,
+    Coordinate,
+    Conductivity,
+    Resistivity,
+    Salinity,
+    Concentration,
+    Density,
+    PH,
+    Voltage,
+    DissolvedOxygenSaturation,
+    Turbidity,
+    Flow,
+    Volume,
+    Percentage,
+    Fluorescence,
+    Current,
+    Velocity,
+}
+
+impl Unit {
+    pub fn dimension(&self) -> Dimension {
+        use Unit::*;
+
+        match self {
+            Celsius | Fahrenheit | Kelvin => Dimension::Temperature,
+            PoundsPerSquareInch | Pascals | Kilopascals | Bars | Millibars
+            | MillimetersOfMercury | InchesOfMercury | CentimetersOfWater | InchesOfWater
+            | Torr | StandardAtmosphere => Dimension::Pressure,
+            Millimeters | Centimeters | Meters | Kilometer | Inches | Feet => Dimension::Length,
+            Degrees | Minutes | Seconds => Dimension::Coordinate,
+            MicrosiemensPerCentimeter | MillisiemensPerCentimeter => Dimension::Conductivity,
+            OhmCentimeters => Dimension::Resistivity,
+            PracticalSalinityUnits | PartsPerThousandSalinity => Dimension::Salinity,
+            PartsPerMillion | PartsPerThousand | MilligramsPerLiter | MicrogramsPerLiter
+            | GramsPerLiter | PartsPerBillion => Dimension::Concentration,
+            GramsPerCubicCentimeter => Dimension::Density,
+            PH => Dimension::PH,
+            Microvolts | Millivolts | Volts => Dimension::Voltage,
+            DissolvedOxygenPercentSaturation => Dimension::DissolvedOxygenSaturation,
+            FormazinNephelometricUnits | NephelometricTurbidityUnits | FormazinTurbidityUnits => {
+                Dimension::Turbidity
+            }
+            CubicFeetPerSecond
+            | CubicFeetPerDay
+            | GallonsPerSecond
+            | GallonsPerMinute
+            | GallonsPerHour
+            | MillionsOfGallonsPerDay
+            | CubicMetersPerSecond
+            | CubicMetersPerHour
+            | LitersPerSecond
+            | MillionsOfLitersPerDay
+            | MillilitersPerMinute
+            | ThousandsOfLitersPerDay
+            | MillilitersPerSecond
+            | MillilitersPerHour
+            | LitersPerMinute
+            | LitersPerHour => Dimension::Flow,
+            CubicFeet | Gallons | MillionsOfGallons | CubicMeters | Liters | AcreFeet
+            | Milliliters | MillionsOfLiters | ThousandsOfLiters | AcreInches => Dimension::Volume,
+            Percent => Dimension::Percentage,
+            RelativeFluorescenceUnits => Dimension::Fluorescence,
+            Microamps | Milliamps | Amps => Dimension::Current,
+            FeetPerSecond | MetersPerSecond => Dimension::Velocity,
+        }
+    }
+
+    /// Scale factor to the base unit of the dimension (Pa, m, m³, m³/s, ...)
+    fn scale(&self) -> Option<f64> {
+        use Unit::*;
+
+        let scale = match self {
+            // Pressure in Pa
+            PoundsPerSquareInch => 6894.757293168,
+            Pascals => 1.0,
+            Kilopascals => 1e3,
+            Bars => 1e5,
+            Millibars => 1e2,
+            MillimetersOfMercury => 133.322387415,
+            InchesOfMercury => 3386.389,
+            CentimetersOfWater => 98.0638,
+            InchesOfWater => 249.082,
+            Torr => 101325.0 / 760.0,
+            StandardAtmosphere => 101325.0,
+            // Length in m
+            Millimeters => 1e-3,
+            Centimeters => 1e-2,
+            Meters => 1.0,
+            Kilometer => 1e3,
+            Inches => 0.0254,
+            Feet => 0.3048,
+            // Coordinate in deg
+            Degrees => 1.0,
+            Minutes => 1.0 / 60.0,
+            Seconds => 1.0 / 3600.0,
+            // Conductivity in µS/cm
+            MicrosiemensPerCentimeter => 1.0,
+            MillisiemensPerCentimeter => 1e3,
+            // Voltage in V
+            Microvolts => 1e-6,
+            Millivolts => 1e-3,
+            Volts => 1.0,
+            // Flow in m³/s
+            CubicFeetPerSecond => 0.028316846592,
+            CubicFeetPerDay => 0.028316846592 / 86400.0,
+            GallonsPerSecond => 0.003785411784,
+            GallonsPerMinute => 0.003785411784 / 60.0,
+            GallonsPerHour => 0.003785411784 / 3600.0,
+            MillionsOfGallonsPerDay => 3785.411784 / 86400.0,
+            CubicMetersPerSecond => 1.0,
+            CubicMetersPerHour => 1.0 / 3600.0,
+            LitersPerSecond => 1e-3,
+            MillionsOfLitersPerDay => 1e3 / 86400.0,
+            MillilitersPerMinute => 1e-6 / 60.0,
+            ThousandsOfLitersPerDay => 1.0 / 86400.0,
+            MillilitersPerSecond => 1e-6,
+            MillilitersPerHour => 1e-6 / 3600.0,
+            LitersPerMinute => 1e-3 / 60.0,
+            LitersPerHour => 1e-3 / 3600.0,
+            // Volume in m³
+            CubicFeet => 0.028316846592,
+            Gallons => 0.003785411784,
+            MillionsOfGallons => 3785.411784,
+            CubicMeters => 1.0,
+            Liters => 1e-3,
+            AcreFeet => 1233.48183754752,
+            Milliliters => 1e-6,
+            MillionsOfLiters => 1e3,
+            ThousandsOfLiters => 1.0,
+            AcreInches => 102.79015312896,
+            // Current in A
+            Microamps => 1e-6,
+            Milliamps => 1e-3,
+            Amps => 1.0,
+            // Velocity in m/s
+            FeetPerSecond => 0.3048,
+            MetersPerSecond => 1.0,
+            _ => return None,
+        };
+
+        Some(scale)
+    }
+
+    fn to_kelvin(self, value: f64) -> f64 {
+        match self {
+            Unit::Celsius => value + 273.15,
+            Unit::Fahrenheit => (value - 32.0) * 5.0 / 9.0 + 273.15,
+            _ => value,
+        }
+    }
+
+    fn of_kelvin(&self, value: f64) -> f64 {
+        match self {
+            Unit::Celsius => value - 273.15,
+            Unit::Fahrenheit => (value - 273.15) * 9.0 / 5.0 + 32.0,
+            _ => value,
+        }
+    }
+
+    /// Convert a value in this unit to another unit of the same dimension
+    pub fn convert(&self, value: f64, to: &Unit) -> Result<f64, AquaTrollLogError> {
+        if self == to {
+            return Ok(value);
+        }
+
+        match (self.dimension(), to.dimension()) {
+            (Dimension::Temperature, Dimension::Temperature) => {
+                Ok(to.of_kelvin(self.to_kelvin(value)))
+            }
+            (from_dim, to_dim) if from_dim == to_dim => match (self.scale(), to.scale()) {
+                (Some(from_scale), Some(to_scale)) => Ok(value * from_scale / to_scale),
+                _ => Err(self.incompatible_with(to)),
+            },
+            _ => Err(self.incompatible_with(to)),
+        }
+    }
+
+    pub(crate) fn incompatible_with(&self, to: &Unit) -> AquaTrollLogError {
+        AquaTrollLogError::IncompatibleUnits {
+            from: self.to_string(),
+            to: to.to_string(),
+        }
+    }
+}
+
+/// Spellings of units in TXT and CSV logs which differ from the display names, as
+/// `(spelling, display name)`
+const UNIT_ALIASES: &[(&str, &str)] = &[
+    ("C", "°C"),
+    ("F", "°F"),
+    ("K", "°K"),
+    ("PSI", "psi"),
+    ("mm Hg", "mmHg"),
+    ("ohm-cm", "Ω-cm"),
+    ("ohm", "Ω-cm"),
+    ("Ω⋅cm", "Ω-cm"),
+    ("g/cm3", "g/cm³"),
+    ("%Sat", "DO % sat"),
+];
+
+/// HTML entities of characters of unit names, as `(entity, character)`
+const UNIT_ENTITIES: &[(&str, char)] = &[
+    ("&micro;", 'µ'),
+    ("&mu;", 'µ'),
+    ("&deg;", '°'),
+    ("&sup2;", '²'),
+    ("&sup3;", '³'),
+    ("&Omega;", 'Ω'),
+    ("&amp;", '&'),
+];
+
+/// Decode named and numeric (e.g. `&#181;` and `&#xB5;`) HTML entities of unit text
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| &rest[..=end]);
+        let c = entity.and_then(|entity| {
+            let code = entity[1..entity.len() - 1].strip_prefix('#');
+            match code {
+                Some(code) => match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code.parse().ok(),
+                }
+                .and_then(char::from_u32),
+                None => UNIT_ENTITIES
+                    .iter()
+                    .find(|(name, _)| *name == entity)
+                    .map(|(_, c)| *c),
+            }
+        });
+        match (entity, c) {
+            (Some(entity), Some(c)) => {
+                decoded.push(c);
+                rest = &rest[entity.len()..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+impl FromStr for Unit {
+    type Err = AquaTrollLogError;
+
+    /// Parse unit from its display name (e.g. `µS/cm`), or its spelling in TXT and CSV logs
+    /// (e.g. `C` and `ohm-cm`), HTML entities (e.g. `&micro;S/cm`) are decoded first
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let decoded = decode_entities(s.trim());
+        let s = decoded.as_str();
+        let display = UNIT_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == s)
+            .map_or(s, |(_, display)| display);
+
+        Unit::iter()
+            .find(|u| u.to_string() == display)
+            .ok_or_else(|| AquaTrollLogError::UnknownUnit(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() <= 1e-6 * b.abs().max(1.0), "{a} != {b}");
+    }
+
+    #[test]
+    fn convert_temperature() {
+        assert_close(
+            Unit::Celsius.convert(100.0, &Unit::Fahrenheit).unwrap(),
+            212.0,
+        );
+        assert_close(
+            Unit::Fahrenheit.convert(32.0, &Unit::Kelvin).unwrap(),
+            273.15,
+        );
+        assert_close(Unit::Kelvin.convert(0.0, &Unit::Celsius).unwrap(), -273.15);
+    }
+
+    #[test]
+    fn convert_pressure() {
+        assert_close(
+            Unit::StandardAtmosphere
+                .convert(1.0, &Unit::Kilopascals)
+                .unwrap(),
+            101.325,
+        );
+        assert_close(
+            Unit::StandardAtmosphere
+                .convert(1.0, &Unit::MillimetersOfMercury)
+                .unwrap(),
+            760.0,
+        );
+        assert_close(
+            Unit::PoundsPerSquareInch
+                .convert(1.0, &Unit::Pascals)
+                .unwrap(),
+            6894.757293168,
+        );
+    }
+
+    #[test]
+    fn convert_length_and_volume() {
+        assert_close(Unit::Feet.convert(1.0, &Unit::Inches).unwrap(), 12.0);
+        assert_close(
+            Unit::Meters.convert(1.0, &Unit::Millimeters).unwrap(),
+            1000.0,
+        );
+        assert_close(
+            Unit::CubicMeters.convert(1.0, &Unit::Liters).unwrap(),
+            1000.0,
+        );
+    }
+
+    #[test]
+    fn convert_incompatible_units() {
+        assert!(matches!(
+            Unit::Celsius.convert(1.0, &Unit::Meters),
+            Err(AquaTrollLogError::IncompatibleUnits { .. })
+        ));
+        assert!(matches!(
+            Unit::PartsPerMillion.convert(1.0, &Unit::MilligramsPerLiter),
+            Err(AquaTrollLogError::IncompatibleUnits { .. })
+        ));
+        assert_eq!(Unit::PH.convert(7.0, &Unit::PH).unwrap(), 7.0);
+        assert_eq!(Unit::Torr.dimension(), Dimension::Pressure);
+    }
+
+    #[test]
+    fn unit_from_str() {
+        assert!(matches!(
+            "µS/cm".parse(),
+            Ok(Unit::MicrosiemensPerCentimeter)
+        ));
+        assert!(matches!("°C".parse(), Ok(Unit::Celsius)));
+        assert!(matches!("C".parse(), Ok(Unit::Celsius)));
+        assert!(matches!("ohm-cm".parse(), Ok(Unit::OhmCentimeters)));
+        assert!(matches!("ohm".parse(), Ok(Unit::OhmCentimeters)));
+        assert!(matches!(
+            "&micro;S/cm".parse(),
+            Ok(Unit::MicrosiemensPerCentimeter)
+        ));
+        assert!(matches!(
+            "&#181;S/cm".parse(),
+            Ok(Unit::MicrosiemensPerCentimeter)
+        ));
+        assert!(matches!("&#xB0;C".parse(), Ok(Unit::Celsius)));
+        assert!(matches!(
+            "&bogus;S/cm".parse::<Unit>(),
+            Err(AquaTrollLogError::UnknownUnit(s)) if s == "&bogus;S/cm"
+        ));
+        assert!(matches!("mL".parse(), Ok(Unit::Milliliters)));
+        assert!(matches!("ML".parse(), Ok(Unit::MillionsOfLiters)));
+        assert!(matches!(
+            "furlong".parse::<Unit>(),
+            Err(AquaTrollLogError::UnknownUnit(s)) if s == "furlong"
+        ));
+    }
+
+    #[test]
+    fn unit_as_key() {
+        let set: std::collections::HashSet<Unit> = [Unit::Celsius, Unit::Kelvin, Unit::Celsius]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+
+        let map: std::collections::BTreeMap<Unit, f64> =
+            [(Unit::Kelvin, 273.15), (Unit::Celsius, 0.0)].into();
+        assert_eq!(map.keys().next(), Some(&Unit::Celsius));
+        assert_eq!(map[&Unit::Kelvin], 273.15);
+    }
+
+    #[test]
+    fn unit_copy() {
+        let from = Unit::Fahrenheit;
+        let targets = [Unit::Celsius, Unit::Kelvin];
+        let converted: Vec<_> = targets
+            .iter()
+            .map(|&to| (to, from.convert(212.0, &to).unwrap()))
+            .collect();
+
+        // `from` and the targets are still usable after being copied
+        assert_eq!(from, Unit::Fahrenheit);
+        assert_eq!(converted[0].0, targets[0]);
+        assert_close(converted[0].1, 100.0);
+        assert_close(converted[1].1, 373.15);
+
+        let parameter = super::super::param::Parameter::Temperature;
+        let pair = (parameter, from);
+        assert_eq!(pair.0, parameter);
+        assert_eq!(pair.1, Unit::Fahrenheit);
+        assert_eq!(parameter.to_string(), "Temperature");
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::NaiveDateTime;
+
+use super::common::{CellValue, Table};
+use crate::error::AquaTrollLogError;
+
+/// Problem of a log file found by `AquaTrollLogReader::validate`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// Column of unknown parameter or unit codes of HTML logs
+    UnknownColumn(String),
+    /// Row which fails to parse, with the line number if known
+    UnparseableRow {
+        line_number: Option<usize>,
+        message: String,
+    },
+    /// `Record Count` of TXT logs not matching the number of data rows
+    RecordCountMismatch { expected: usize, found: usize },
+    /// Timestamp of the row, by index of data rows, not later than the previous timestamp
+    NonMonotonicTime {
+        row: usize,
+        timestamp: NaiveDateTime,
+    },
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationIssue::UnknownColumn(name) => write!(f, "Unknown column: {name}"),
+            ValidationIssue::UnparseableRow {
+                line_number: Some(line_number),
+                message,
+            } => write!(f, "Line {line_number}: {message}"),
+            ValidationIssue::UnparseableRow {
+                line_number: None,
+                message,
+            } => write!(f, "Unparseable row: {message}"),
+            ValidationIssue::RecordCountMismatch { expected, found } => {
+                write!(f, "Record count {expected}, found {found} rows")
+            }
+            ValidationIssue::NonMonotonicTime { row, timestamp } => {
+                write!(
+                    f,
+                    "Row {row}: timestamp {timestamp} not after the previous one"
+                )
+            }
+        }
+    }
+}
+
+/// Issue of a row failing to parse, `None` for errors other than of the content of a row
+pub(crate) fn unparseable_row(
+    error: &AquaTrollLogError,
+    line_number: Option<usize>,
+) -> Option<ValidationIssue> {
+    let line_number = match error {
+        AquaTrollLogError::ColumnCountMismatch { line_number, .. } => Some(*line_number),
+        AquaTrollLogError::ParseFloatError(_)
+        | AquaTrollLogError::ChronoParseError(_)
+        | AquaTrollLogError::InvalidBoolean(_)
+        | AquaTrollLogError::InvalidInteger { .. } => line_number,
+        AquaTrollLogError::CsvError(e) => e.position().map(|p| p.line() as usize),
+        _ => return None,
+    };
+    Some(ValidationIssue::UnparseableRow {
+        line_number,
+        message: error.to_string(),
+    })
+}
+
+/// Issues of columns of unknown codes of HTML logs, named `Unknown` and `Unknown_01`, ...
+fn unknown_columns(columns: &[String]) -> Vec<ValidationIssue> {
+    columns
+        .iter()
+        .filter(|name| *name == "Unknown" || name.starts_with("Unknown_"))
+        .map(|name| ValidationIssue::UnknownColumn(name.clone()))
+        .collect()
+}
+
+/// Checks timestamps of rows to be increasing
+#[derive(Default)]
+pub(crate) struct MonotonicTime {
+    previous: Option<NaiveDateTime>,
+}
+
+impl MonotonicTime {
+    /// Check the timestamp of the row of the index
+    pub(crate) fn check(
+        &mut self,
+        row: usize,
+        timestamp: Option<NaiveDateTime>,
+    ) -> Option<ValidationIssue> {
+        let timestamp = timestamp?;
+        let previous = self.previous.replace(timestamp);
+        (previous >= Some(timestamp))
+            .then_some(ValidationIssue::NonMonotonicTime { row, timestamp })
+    }
+}
+
+/// Issues of the columns and timestamps of a table
+pub(crate) fn table_issues(table: &Table) -> Vec<ValidationIssue> {
+    let mut issues = unknown_columns(&table.columns);
+    let Some(index) = table.datetime_column() else {
+        return issues;
+    };
+
+    let mut monotonic_time = MonotonicTime::default();
+    for (i, row) in table.rows.iter().enumerate() {
+        let timestamp = match row[index] {
+            CellValue::DateTime(dt) => Some(dt),
+            _ => None,
+        };
+        issues.extend(monotonic_time.check(i, timestamp));
+    }
+    issues
+}
@@ -0,0 +1,109 @@
+/// Recoverable problem of a log file found while reading it
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReadWarning {
+    /// Row of a TXT table not spanning all columns, read with the missing cells empty
+    ShortRow {
+        line_number: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// Row of a TXT table of the timestamp merged with the first reading, split into columns
+    RecoveredRow { line_number: usize },
+    /// Row of a CSV log of a different length than the header, padded or truncated
+    ResizedRow {
+        line_number: Option<usize>,
+        expected: usize,
+        found: usize,
+    },
+    /// Column of the log data named with a unit which isn't a known one
+    UnknownUnit { column: String, unit: String },
+    /// `Record Count` of a TXT log not matching the number of rows read
+    RecordCountMismatch { expected: usize, found: usize },
+    /// Column to be renamed not found in the log data
+    RenamedColumnNotFound(String),
+    /// Columns of interleaved notes not matching the log note, the notes are left in the log
+    /// data
+    NoteColumnsMismatch(Vec<String>),
+    /// Section member of a HTML log not of a label and value, skipped
+    UnparsedAttribute(String),
+    /// Code of a HTML column header which isn't an integer, read as absent
+    MalformedCode { attribute: String, value: String },
+    /// Parameter column of a HTML log of a sensor without a serial, not listed in the sensors
+    SensorSerialNotFound(String),
+    /// Parameter column of a HTML log of a sensor without a type, not listed in the sensors
+    SensorTypeNotFound(String),
+    /// Logs of known and unknown time zones concatenated, timestamps of unknown time zones
+    /// taken as UTC
+    MixedTimeZones,
+    /// Entry of an archive not of a known log format, skipped
+    UnknownFormat(String),
+    /// Entry of an archive failing to be read, skipped
+    UnreadableEntry { name: String, error: String },
+}
+
+impl std::fmt::Display for ReadWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadWarning::ShortRow {
+                line_number,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Line {line_number}: {found} of {expected} columns, missing cells left empty"
+            ),
+            ReadWarning::RecoveredRow { line_number } => {
+                write!(
+                    f,
+                    "Line {line_number}: timestamp merged with the first reading"
+                )
+            }
+            ReadWarning::ResizedRow {
+                line_number,
+                expected,
+                found,
+            } => {
+                if let Some(line_number) = line_number {
+                    write!(f, "Line {line_number}: ")?;
+                }
+                write!(f, "{found} cells resized to {expected} columns")
+            }
+            ReadWarning::UnknownUnit { column, unit } => {
+                write!(f, "{column}: Unknown unit {unit}")
+            }
+            ReadWarning::RecordCountMismatch { expected, found } => {
+                write!(f, "Record Count {expected}, but {found} rows read")
+            }
+            ReadWarning::RenamedColumnNotFound(name) => {
+                write!(f, "{name}: Column to be renamed not found")
+            }
+            ReadWarning::NoteColumnsMismatch(columns) => write!(
+                f,
+                "Interleaved notes of columns {} don't match the log note, left in the log data",
+                columns.join(", ")
+            ),
+            ReadWarning::UnparsedAttribute(text) => {
+                write!(f, "{text}: Attribute of no label and value, skipped")
+            }
+            ReadWarning::MalformedCode { attribute, value } => {
+                write!(f, "{attribute}: Malformed code '{value}', ignored")
+            }
+            ReadWarning::SensorSerialNotFound(parameter) => {
+                write!(f, "{parameter}: Sensor serial not found")
+            }
+            ReadWarning::SensorTypeNotFound(parameter) => {
+                write!(f, "{parameter}: Sensor type not found")
+            }
+            ReadWarning::MixedTimeZones => write!(
+                f,
+                "Logs of known and unknown time zones concatenated, unknown ones taken as UTC"
+            ),
+            ReadWarning::UnknownFormat(name) => {
+                write!(f, "{name}: Unknown log format, skipped")
+            }
+            ReadWarning::UnreadableEntry { name, error } => {
+                write!(f, "{name}: {error}, skipped")
+            }
+        }
+    }
+}